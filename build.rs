@@ -0,0 +1,420 @@
+//! Generate game data tables from the reports of the vanilla data generator.
+//!
+//! The reports are produced with
+//! `java -DbundlerMainClass=net.minecraft.data.Main -jar server.jar --reports`
+//! and the content of `generated/reports` should be copied to `data/reports` (or pointed to by
+//! the `MINECRAFT_REPORTS_DIR` environment variable).
+//! Without them the tables are generated empty, with a warning: every block is then unknown and
+//! solid, so the bot can't do much. Setting `MINECRAFT_REQUIRE_REPORTS` makes the build fail
+//! instead, for release builds.
+//!
+//! The hardness of the blocks isn't part of the reports, it comes from `data/hardness.txt`, and the
+//! blocks needing the correct tool to drop anything from `data/requires_tool.txt`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{Map, Value};
+
+const DEFAULT_REPORTS_DIR: &str = "data/reports";
+const REQUIRE_REPORTS_VAR: &str = "MINECRAFT_REQUIRE_REPORTS";
+const HARDNESS_FILE: &str = "data/hardness.txt";
+const REQUIRES_TOOL_FILE: &str = "data/requires_tool.txt";
+
+/// Block types (`definition.type` in the report) without collision
+const NON_SOLID_TYPES: &[&str] = &[
+    "minecraft:air",
+    "minecraft:liquid",
+    "minecraft:attached_stem",
+    "minecraft:banner",
+    "minecraft:base_coral_fan",
+    "minecraft:base_coral_plant",
+    "minecraft:base_coral_wall_fan",
+    "minecraft:beetroot",
+    "minecraft:big_dripleaf_stem",
+    "minecraft:bubble_column",
+    "minecraft:bush",
+    "minecraft:button",
+    "minecraft:cactus_flower",
+    "minecraft:carrot",
+    "minecraft:cave_vines",
+    "minecraft:cave_vines_plant",
+    "minecraft:ceiling_hanging_sign",
+    "minecraft:coral_fan",
+    "minecraft:coral_plant",
+    "minecraft:coral_wall_fan",
+    "minecraft:crop",
+    "minecraft:dead_bush",
+    "minecraft:detector_rail",
+    "minecraft:double_plant",
+    "minecraft:dry_vegetation",
+    "minecraft:end_gateway",
+    "minecraft:end_portal",
+    "minecraft:eyeblossom",
+    "minecraft:fire",
+    "minecraft:firefly_bush",
+    "minecraft:flower",
+    "minecraft:flower_bed",
+    "minecraft:frogspawn",
+    "minecraft:fungus",
+    "minecraft:glow_lichen",
+    "minecraft:hanging_roots",
+    "minecraft:kelp",
+    "minecraft:kelp_plant",
+    "minecraft:leaf_litter",
+    "minecraft:lever",
+    "minecraft:light",
+    "minecraft:mushroom",
+    "minecraft:nether_portal",
+    "minecraft:nether_sprouts",
+    "minecraft:nether_wart",
+    "minecraft:pitcher_crop",
+    "minecraft:potato",
+    "minecraft:powered_rail",
+    "minecraft:pressure_plate",
+    "minecraft:rail",
+    "minecraft:redstone_torch",
+    "minecraft:redstone_wall_torch",
+    "minecraft:redstone_wire",
+    "minecraft:roots",
+    "minecraft:sapling",
+    "minecraft:sculk_vein",
+    "minecraft:seagrass",
+    "minecraft:short_dry_grass",
+    "minecraft:small_dripleaf",
+    "minecraft:soul_fire",
+    "minecraft:spore_blossom",
+    "minecraft:standing_sign",
+    "minecraft:stem",
+    "minecraft:structure_void",
+    "minecraft:sugar_cane",
+    "minecraft:sweet_berry_bush",
+    "minecraft:tall_dry_grass",
+    "minecraft:tall_flower",
+    "minecraft:tall_grass",
+    "minecraft:tall_seagrass",
+    "minecraft:torch",
+    "minecraft:torchflower_crop",
+    "minecraft:trip_wire",
+    "minecraft:trip_wire_hook",
+    "minecraft:twisting_vines",
+    "minecraft:twisting_vines_plant",
+    "minecraft:vine",
+    "minecraft:wall_banner",
+    "minecraft:wall_hanging_sign",
+    "minecraft:wall_sign",
+    "minecraft:wall_torch",
+    "minecraft:web",
+    "minecraft:weeping_vines",
+    "minecraft:weeping_vines_plant",
+    "minecraft:weighted_pressure_plate",
+    "minecraft:wither_rose",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=MINECRAFT_REPORTS_DIR");
+    println!("cargo:rerun-if-env-changed={}", REQUIRE_REPORTS_VAR);
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+
+    let blocks = read_report("blocks.json");
+    let hardness = read_hardness();
+    let requires_tool = read_requires_tool();
+    fs::write(
+        out_dir.join("blocks.rs"),
+        generate_blocks(blocks.as_ref(), &hardness, &requires_tool),
+    )
+    .expect("Can't write blocks.rs");
+
+    let registries = read_report("registries.json");
+    let items = read_report("items.json");
+    fs::write(
+        out_dir.join("items.rs"),
+        generate_items(registries.as_ref(), items.as_ref()),
+    )
+    .expect("Can't write items.rs");
+}
+
+fn read_report(name: &str) -> Option<Value> {
+    let dir = env::var("MINECRAFT_REPORTS_DIR").unwrap_or(DEFAULT_REPORTS_DIR.to_string());
+    let path = Path::new(&dir).join(name);
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let Ok(content) = fs::read_to_string(&path) else {
+        if env::var_os(REQUIRE_REPORTS_VAR).is_some() {
+            panic!(
+                "{} not found, generate the reports as described in the README",
+                path.display()
+            );
+        }
+        println!(
+            "cargo:warning={} not found, the generated tables will be empty (see the README)",
+            path.display()
+        );
+        return None;
+    };
+
+    Some(serde_json::from_str(&content).unwrap_or_else(|e| panic!("Invalid {}: {}", name, e)))
+}
+
+/// Lines of a hardness followed by the names of the blocks having it, without namespace
+fn read_hardness() -> HashMap<String, f32> {
+    println!("cargo:rerun-if-changed={}", HARDNESS_FILE);
+    let content = fs::read_to_string(HARDNESS_FILE)
+        .unwrap_or_else(|e| panic!("Can't read {}: {}", HARDNESS_FILE, e));
+
+    let mut hardness = HashMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let value: f32 = words
+            .next()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| panic!("Invalid hardness in {}: {}", HARDNESS_FILE, line));
+        for name in words {
+            if hardness
+                .insert(format!("minecraft:{}", name), value)
+                .is_some()
+            {
+                panic!("{} is listed twice in {}", name, HARDNESS_FILE);
+            }
+        }
+    }
+    hardness
+}
+
+/// Names of blocks without namespace, separated by whitespace
+fn read_requires_tool() -> HashSet<String> {
+    println!("cargo:rerun-if-changed={}", REQUIRES_TOOL_FILE);
+    let content = fs::read_to_string(REQUIRES_TOOL_FILE)
+        .unwrap_or_else(|e| panic!("Can't read {}: {}", REQUIRES_TOOL_FILE, e));
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(str::split_whitespace)
+        .map(|name| format!("minecraft:{}", name))
+        .collect()
+}
+
+fn generate_blocks(
+    report: Option<&Value>,
+    hardness: &HashMap<String, f32>,
+    requires_tool: &HashSet<String>,
+) -> String {
+    let empty = Map::new();
+    let blocks = report
+        .map(|r| r.as_object().expect("blocks.json should be an object"))
+        .unwrap_or(&empty);
+
+    // Block IDs are the indices in the report (its order is kept thanks to the `preserve_order`
+    // feature of serde_json), state IDs are given explicitly
+    let mut states: Vec<Option<String>> = Vec::new();
+    let mut blocks_code = String::new();
+    let mut missing_hardness = Vec::new();
+
+    for (block_id, (name, block)) in blocks.iter().enumerate() {
+        let block_type = block["definition"]["type"]
+            .as_str()
+            .expect("Block should have a type");
+
+        let property_names: Vec<&String> = block
+            .get("properties")
+            .and_then(Value::as_object)
+            .map(|p| p.keys().collect())
+            .unwrap_or_default();
+
+        let block_states = block["states"]
+            .as_array()
+            .expect("Block should have states");
+        let mut default_state = None;
+        let mut first_state = i64::MAX;
+
+        for state in block_states {
+            let id = state["id"].as_i64().expect("State should have an id");
+            first_state = first_state.min(id);
+            if state.get("default").and_then(Value::as_bool) == Some(true) {
+                default_state = Some(id);
+            }
+
+            let values: Vec<&str> = property_names
+                .iter()
+                .map(|p| {
+                    state["properties"][p.as_str()]
+                        .as_str()
+                        .expect("State should have a value for each property")
+                })
+                .collect();
+
+            let id = id as usize;
+            if states.len() <= id {
+                states.resize(id + 1, None);
+            }
+            states[id] = Some(format!(
+                "StateInfo {{ block: {}, values: &{:?} }}",
+                block_id, values
+            ));
+        }
+
+        let flags = match block_type {
+            "minecraft:air" => "BlockFlags::AIR",
+            "minecraft:liquid" => "BlockFlags::LIQUID",
+            _ if !NON_SOLID_TYPES.contains(&block_type) => "BlockFlags::SOLID",
+            _ => "BlockFlags::empty()",
+        };
+
+        let block_hardness = hardness.get(name);
+        if block_hardness.is_none() {
+            missing_hardness.push(name.as_str());
+        }
+
+        writeln!(
+            blocks_code,
+            "    BlockInfo {{ name: {:?}, block_type: {:?}, properties: &{:?}, default_state: {}, first_state: {}, flags: {}, hardness: {:?}, requires_correct_tool: {} }},",
+            name,
+            block_type,
+            property_names,
+            default_state.unwrap_or(first_state),
+            first_state,
+            flags,
+            block_hardness,
+            requires_tool.contains(name),
+        )
+        .unwrap();
+    }
+
+    if !missing_hardness.is_empty() {
+        println!(
+            "cargo:warning=No hardness in {} for {}",
+            HARDNESS_FILE,
+            missing_hardness.join(", ")
+        );
+    }
+    let mut unknown: Vec<&str> = hardness
+        .keys()
+        .filter(|name| !blocks.is_empty() && !blocks.contains_key(name.as_str()))
+        .map(String::as_str)
+        .collect();
+    unknown.sort();
+    if !unknown.is_empty() {
+        println!(
+            "cargo:warning=Unknown blocks in {}: {}",
+            HARDNESS_FILE,
+            unknown.join(", ")
+        );
+    }
+    let mut unknown: Vec<&str> = requires_tool
+        .iter()
+        .filter(|name| !blocks.is_empty() && !blocks.contains_key(name.as_str()))
+        .map(String::as_str)
+        .collect();
+    unknown.sort();
+    if !unknown.is_empty() {
+        println!(
+            "cargo:warning=Unknown blocks in {}: {}",
+            REQUIRES_TOOL_FILE,
+            unknown.join(", ")
+        );
+    }
+
+    let mut code = String::new();
+    writeln!(code, "// Generated by build.rs from blocks.json").unwrap();
+    writeln!(
+        code,
+        "pub static BLOCKS: &[BlockInfo] = &[\n{}];",
+        blocks_code
+    )
+    .unwrap();
+    writeln!(code, "pub static STATES: &[StateInfo] = &[").unwrap();
+    for (id, state) in states.into_iter().enumerate() {
+        let state = state.unwrap_or_else(|| panic!("Missing state {}", id));
+        writeln!(code, "    {},", state).unwrap();
+    }
+    writeln!(code, "];").unwrap();
+    code
+}
+
+fn generate_items(registries: Option<&Value>, items: Option<&Value>) -> String {
+    // The item registry is not sent by the server, the IDs come from the registries report
+    let mut names: Vec<Option<&str>> = Vec::new();
+    if let Some(entries) = registries
+        .and_then(|r| r.get("minecraft:item"))
+        .and_then(|r| r["entries"].as_object())
+    {
+        for (name, entry) in entries {
+            let id = entry["protocol_id"]
+                .as_u64()
+                .expect("Item should have a protocol_id") as usize;
+            if names.len() <= id {
+                names.resize(id + 1, None);
+            }
+            names[id] = Some(name);
+        }
+    }
+
+    let mut code = String::new();
+    writeln!(
+        code,
+        "// Generated by build.rs from registries.json and items.json"
+    )
+    .unwrap();
+    writeln!(code, "pub static ITEMS: &[ItemInfo] = &[").unwrap();
+    for (id, name) in names.into_iter().enumerate() {
+        let name = name.unwrap_or_else(|| panic!("Missing item {}", id));
+        // Default components of the item, only the ones used by the client are kept
+        let components = items.and_then(|i| i.get(name)).map(|i| &i["components"]);
+        let component = |key: &str| components.and_then(|c| c.get(key)).and_then(Value::as_i64);
+        let tool = components
+            .and_then(|c| c.get("minecraft:tool"))
+            .map_or("None".to_string(), generate_tool);
+        writeln!(
+            code,
+            "    ItemInfo {{ name: {:?}, max_stack_size: {}, max_damage: {}, tool: {} }},",
+            name,
+            component("minecraft:max_stack_size").unwrap_or(64),
+            component("minecraft:max_damage").unwrap_or(0),
+            tool,
+        )
+        .unwrap();
+    }
+    writeln!(code, "];").unwrap();
+    code
+}
+
+/// The blocks of a rule are a block, a tag (`#minecraft:mineable/pickaxe`) or a list of blocks
+fn generate_tool(tool: &Value) -> String {
+    let rules: Vec<String> = tool["rules"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|rule| {
+            let blocks: Vec<&str> = match &rule["blocks"] {
+                Value::String(block) => vec![block],
+                Value::Array(blocks) => blocks.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            format!(
+                "ToolRuleInfo {{ blocks: &{:?}, speed: {:?}, correct_for_drops: {:?} }}",
+                blocks,
+                rule.get("speed").and_then(Value::as_f64).map(|s| s as f32),
+                rule.get("correct_for_drops").and_then(Value::as_bool),
+            )
+        })
+        .collect();
+    format!(
+        "Some(ToolInfo {{ rules: &[{}], default_mining_speed: {:?} }})",
+        rules.join(", "),
+        tool.get("default_mining_speed")
+            .and_then(Value::as_f64)
+            .unwrap_or(1.) as f32,
+    )
+}
@@ -1,20 +1,29 @@
 #![allow(dead_code)]
 
 mod bitset;
+mod text_component;
 mod varint;
 
 pub use bitset::*;
 use macros::{Deserialize, Serialize};
+pub use text_component::*;
 pub use varint::*;
 
 use core::slice;
 use std::{
-    fmt::Debug, io::{Read, Write}, mem::MaybeUninit, ops::Deref
+    collections::HashMap,
+    fmt::Debug,
+    io::{Read, Write},
+    mem::MaybeUninit,
+    ops::Deref,
 };
 
 use crate::{
     data::{Deserialize, DeserializeError, Serialize, SerializeError},
-    game::{IdSet, Slot, StructuredComponent, Vec3i},
+    game::{
+        BlockPredicate, EntityMetadata, IdOr, IdSet, MetadataValue, Particle, ParticleData,
+        PositionSource, Slot, SoundEvent, StructuredComponent, Vec3, Vec3i,
+    },
 };
 
 impl Serialize for bool {
@@ -367,7 +376,7 @@ impl<T: Deserialize> Deserialize for Box<T> {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct BlockPos(pub Vec3i);
 
 impl Deserialize for BlockPos {
@@ -382,6 +391,19 @@ impl Deserialize for BlockPos {
     }
 }
 
+impl Serialize for BlockPos {
+    fn size(&self) -> usize {
+        8
+    }
+
+    fn serialize(&self, stream: &mut dyn std::io::Write) -> Result<(), SerializeError> {
+        let Vec3i { x, y, z } = self.0;
+        let val =
+            ((x as i64 & 0x3FF_FFFF) << 38) | ((z as i64 & 0x3FF_FFFF) << 12) | (y as i64 & 0xFFF);
+        val.serialize(stream)
+    }
+}
+
 impl Deserialize for IdSet {
     fn deserialize(stream: &mut crate::data::DataStream) -> Result<Self, DeserializeError> {
         let type_ = VarInt::deserialize(stream)?.0;
@@ -414,9 +436,97 @@ impl Deserialize for IdSet {
     }
 }
 
-impl Deserialize for StructuredComponent {
-    fn deserialize(_stream: &mut crate::data::DataStream) -> Result<Self, DeserializeError> {
-        todo!()
+impl Deserialize for SoundEvent {
+    fn deserialize(stream: &mut crate::data::DataStream) -> Result<Self, DeserializeError> {
+        let id = VarInt::deserialize(stream)?.0;
+        if id == 0 {
+            Ok(Self::Inline {
+                name: String::deserialize(stream)?,
+                fixed_range: Option::deserialize(stream)?,
+            })
+        } else {
+            Ok(Self::Registry(id - 1))
+        }
+    }
+}
+
+impl Deserialize for BlockPredicate {
+    fn deserialize(stream: &mut crate::data::DataStream) -> Result<Self, DeserializeError> {
+        Ok(Self {
+            blocks: Option::deserialize(stream)?,
+            properties: Option::deserialize(stream)?,
+            nbt: Option::deserialize(stream)?,
+            exact_components: Vec::deserialize(stream)?,
+            partial_components: Vec::deserialize(stream)?,
+        })
+    }
+}
+
+impl<T: Deserialize> Deserialize for IdOr<T> {
+    fn deserialize(stream: &mut crate::data::DataStream) -> Result<Self, DeserializeError> {
+        let id = VarInt::deserialize(stream)?.0;
+        if id == 0 {
+            Ok(Self::Inline(T::deserialize(stream)?))
+        } else {
+            Ok(Self::Registry(id - 1))
+        }
+    }
+}
+
+impl Deserialize for EntityMetadata {
+    fn deserialize(stream: &mut crate::data::DataStream) -> Result<Self, DeserializeError> {
+        const END: u8 = 0xff;
+
+        let mut fields = HashMap::new();
+        loop {
+            let index = u8::deserialize(stream)?;
+            if index == END {
+                return Ok(Self(fields));
+            }
+            fields.insert(index, MetadataValue::deserialize(stream)?);
+        }
+    }
+}
+
+impl Deserialize for Particle {
+    fn deserialize(stream: &mut crate::data::DataStream) -> Result<Self, DeserializeError> {
+        let id = VarInt::deserialize(stream)?.0;
+        // IDs in the `minecraft:particle_type` registry of the particles with options
+        let data = match id {
+            1 | 2 | 28 | 108 | 112 => ParticleData::BlockState(VarInt::deserialize(stream)?),
+            13 => ParticleData::Dust {
+                color: i32::deserialize(stream)?,
+                scale: f32::deserialize(stream)?,
+            },
+            14 => ParticleData::DustColorTransition {
+                from: i32::deserialize(stream)?,
+                to: i32::deserialize(stream)?,
+                scale: f32::deserialize(stream)?,
+            },
+            20 | 35 => ParticleData::Color(i32::deserialize(stream)?),
+            15 | 45 => ParticleData::Spell {
+                color: i32::deserialize(stream)?,
+                power: f32::deserialize(stream)?,
+            },
+            37 => ParticleData::SculkCharge {
+                roll: f32::deserialize(stream)?,
+            },
+            46 => ParticleData::Item(Slot::deserialize(stream)?),
+            47 => ParticleData::Vibration {
+                source: PositionSource::deserialize(stream)?,
+                ticks: VarInt::deserialize(stream)?,
+            },
+            48 => ParticleData::Trail {
+                target: Vec3::deserialize(stream)?,
+                color: i32::deserialize(stream)?,
+                duration: VarInt::deserialize(stream)?,
+            },
+            102 => ParticleData::Shriek {
+                delay: VarInt::deserialize(stream)?,
+            },
+            _ => ParticleData::None,
+        };
+        Ok(Self { id, data })
     }
 }
 
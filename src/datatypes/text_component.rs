@@ -0,0 +1,64 @@
+use crate::{
+    data::{DataStream, Deserialize, DeserializeError},
+    nbt::Nbt,
+};
+
+/// A text component, sent as network NBT (the root can be a string or a compound)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextComponent(pub Nbt);
+
+impl TextComponent {
+    /// Flatten the component to its text, without any styling
+    pub fn to_plain(&self) -> String {
+        let mut out = String::new();
+        Self::append_plain(&self.0, &mut out);
+        out
+    }
+
+    fn append_plain(nbt: &Nbt, out: &mut String) {
+        match nbt {
+            Nbt::String(s) => out.push_str(s),
+            Nbt::List(list) => list.iter().for_each(|v| Self::append_plain(v, out)),
+            Nbt::Compound(_) => {
+                if let Some(text) = nbt.get("text").and_then(Nbt::as_str) {
+                    out.push_str(text);
+                } else if let Some(key) = nbt.get("translate").and_then(Nbt::as_str) {
+                    match nbt.get("fallback").and_then(Nbt::as_str) {
+                        Some(fallback) => out.push_str(fallback),
+                        None => out.push_str(key),
+                    }
+                    if let Some(Nbt::List(args)) = nbt.get("with") {
+                        out.push_str(" [");
+                        for (i, arg) in args.iter().enumerate() {
+                            if i > 0 {
+                                out.push_str(", ");
+                            }
+                            Self::append_plain(arg, out);
+                        }
+                        out.push(']');
+                    }
+                } else if let Some(inner) = nbt.get("") {
+                    // Heterogeneous lists wrap their elements in a compound with an empty key
+                    Self::append_plain(inner, out);
+                }
+
+                if let Some(extra) = nbt.get("extra") {
+                    Self::append_plain(extra, out);
+                }
+            }
+            Nbt::Byte(v) => out.push_str(&v.to_string()),
+            Nbt::Short(v) => out.push_str(&v.to_string()),
+            Nbt::Int(v) => out.push_str(&v.to_string()),
+            Nbt::Long(v) => out.push_str(&v.to_string()),
+            Nbt::Float(v) => out.push_str(&v.to_string()),
+            Nbt::Double(v) => out.push_str(&v.to_string()),
+            _ => (),
+        }
+    }
+}
+
+impl Deserialize for TextComponent {
+    fn deserialize(stream: &mut DataStream) -> Result<Self, DeserializeError> {
+        Ok(Self(Nbt::deserialize_any(stream)?))
+    }
+}
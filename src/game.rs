@@ -1,23 +1,75 @@
+mod block;
+mod chat;
+mod commands;
+mod components;
+mod dig;
 pub mod entities;
 mod entity;
+mod entity_type;
 mod gameloop;
+mod interaction;
+mod inventory;
+mod item;
+mod metadata;
+mod motion;
+mod pathfinding;
+mod physics;
 mod player;
+mod player_list;
+mod recipes;
+mod registries;
+mod shapes;
+pub mod tags;
 mod types;
+mod window;
 pub mod world;
 
+pub use block::*;
+pub use chat::*;
+pub use commands::*;
+pub use components::*;
+pub use dig::*;
 pub use entities::{Entities, EntityRef};
 pub use entity::*;
+pub use entity_type::*;
 pub use gameloop::*;
-pub use player::Player;
+pub use interaction::*;
+pub use inventory::*;
+pub use item::*;
+pub use metadata::*;
+pub use pathfinding::*;
+pub use physics::*;
+pub use player::{MobEffect, Player};
+pub use player_list::*;
+pub use recipes::*;
+pub use registries::*;
+pub use tags::Tags;
 use thiserror::Error;
 pub use types::*;
+pub use window::*;
 
-use crate::game::world::World;
+use crate::{datatypes::BlockPos, game::world::World};
 
 #[derive(Debug, Error)]
 pub enum GameError {
     #[error("Unknown entity {0}")]
     UnkonwnEntity(EntityId),
+    #[error("The server enforces secure chat, unsigned messages can't be sent")]
+    SecureChatEnforced,
+    #[error("Chat message too long ({0} characters)")]
+    MessageTooLong(usize),
+    #[error("Unknown window {0}")]
+    UnknownWindow(i32),
+    #[error("Slot {0} out of the window")]
+    InvalidSlot(usize),
+    #[error("Can't dig the block at {0:?}")]
+    CantDig(BlockPos),
+    #[error("No path to {0:?}")]
+    NoPath(BlockPos),
+    #[error("No path found in {0} nodes")]
+    PathNodeLimit(usize),
+    #[error("Stuck while moving")]
+    Stuck,
 }
 
 #[derive(Debug, Default)]
@@ -25,4 +77,16 @@ pub struct Game {
     pub player: Player,
     pub entities: Entities,
     pub world: World,
+    pub chat: Chat,
+    pub commands: Commands,
+    pub player_list: PlayerList,
+    pub registries: Registries,
+    pub tags: Tags,
+    pub recipes: Recipes,
+    pub inventory: Inventory,
+    pub windows: Windows,
+    pub digging: Option<Digging>,
+    pub predictions: BlockPredictions,
+    pub movement: MovementController,
+    pub behaviors: BehaviorScheduler,
 }
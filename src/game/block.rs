@@ -0,0 +1,156 @@
+use std::fmt::{Debug, Display};
+
+bitflags::bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct BlockFlags: u8 {
+        const AIR = 1;
+        const LIQUID = 2;
+        /// Has a collision box
+        const SOLID = 4;
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct BlockInfo {
+    pub name: &'static str,
+    /// Block class in the vanilla code (e.g. `minecraft:stair`)
+    pub block_type: &'static str,
+    pub properties: &'static [&'static str],
+    pub default_state: i32,
+    pub first_state: i32,
+    pub flags: BlockFlags,
+    /// Time to break the block with the hand, -1 if it can't be broken, `None` if not listed in
+    /// `data/hardness.txt`
+    pub hardness: Option<f32>,
+    /// Drops nothing unless mined with the correct tool, from `data/requires_tool.txt`
+    pub requires_correct_tool: bool,
+}
+
+#[derive(Debug)]
+pub struct StateInfo {
+    /// Index in `BLOCKS`, which is also the block ID
+    pub block: u16,
+    /// Value of each property of the block, in the same order
+    pub values: &'static [&'static str],
+}
+
+include!(concat!(env!("OUT_DIR"), "/blocks.rs"));
+
+/// A block state ID, as sent by the server
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BlockState(pub i32);
+
+impl BlockState {
+    pub const AIR: Self = Self(0);
+
+    fn state_info(self) -> Option<&'static StateInfo> {
+        usize::try_from(self.0).ok().and_then(|id| STATES.get(id))
+    }
+
+    pub fn block(self) -> Option<&'static BlockInfo> {
+        self.state_info().map(|s| &BLOCKS[s.block as usize])
+    }
+
+    /// ID of the block in the block registry
+    pub fn block_id(self) -> Option<i32> {
+        self.state_info().map(|s| s.block as i32)
+    }
+
+    /// Name of the block (e.g. `minecraft:oak_log`), `minecraft:unknown` if not in the table
+    pub fn name(self) -> &'static str {
+        self.block().map_or("minecraft:unknown", |b| b.name)
+    }
+
+    pub fn property(self, name: &str) -> Option<&'static str> {
+        let state = self.state_info()?;
+        let block = &BLOCKS[state.block as usize];
+        let idx = block.properties.iter().position(|&p| p == name)?;
+        Some(state.values[idx])
+    }
+
+    pub fn properties(self) -> impl Iterator<Item = (&'static str, &'static str)> {
+        self.state_info().into_iter().flat_map(|s| {
+            BLOCKS[s.block as usize]
+                .properties
+                .iter()
+                .copied()
+                .zip(s.values.iter().copied())
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn default_state(self) -> Self {
+        self.block().map_or(self, |b| Self(b.default_state))
+    }
+
+    fn flags(self) -> BlockFlags {
+        match self.block() {
+            Some(b) => b.flags,
+            // Without the table, only the air is known
+            None if self == Self::AIR => BlockFlags::AIR,
+            None => BlockFlags::SOLID,
+        }
+    }
+
+    pub fn is_air(self) -> bool {
+        self.flags().contains(BlockFlags::AIR)
+    }
+
+    #[allow(dead_code)]
+    pub fn is_liquid(self) -> bool {
+        self.flags().contains(BlockFlags::LIQUID)
+    }
+
+    pub fn is_solid(self) -> bool {
+        self.flags().contains(BlockFlags::SOLID)
+    }
+
+    /// Water source or flowing water, or a waterlogged block
+    pub fn is_water(self) -> bool {
+        self.name() == "minecraft:water" || self.is_waterlogged()
+    }
+
+    pub fn is_waterlogged(self) -> bool {
+        self.property("waterlogged") == Some("true")
+    }
+
+    /// Find the state of a block with some properties, the others having their default value
+    pub fn from_name(name: &str, properties: &[(&str, &str)]) -> Option<Self> {
+        let (block_id, block) = BLOCKS.iter().enumerate().find(|(_, b)| b.name == name)?;
+        let default = Self(block.default_state);
+        let wanted = |prop: &str| {
+            properties
+                .iter()
+                .find(|(p, _)| *p == prop)
+                .map(|(_, v)| *v)
+                .or_else(|| default.property(prop))
+        };
+
+        let state_count = STATES[block.first_state as usize..]
+            .iter()
+            .take_while(|s| s.block as usize == block_id)
+            .count();
+        (block.first_state..block.first_state + state_count as i32)
+            .map(Self)
+            .find(|state| state.properties().all(|(p, v)| wanted(p) == Some(v)))
+    }
+}
+
+impl Display for BlockState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())?;
+        let mut properties = self.properties().peekable();
+        if properties.peek().is_some() {
+            let properties: Vec<_> = properties.map(|(p, v)| format!("{}={}", p, v)).collect();
+            write!(f, "[{}]", properties.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for BlockState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BlockState({}, {})", self.0, self)
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    datatypes::VarInt,
+    game::GameError,
+    packets::{ChatCommand, ChatMessage, MessageSignature, ReceiveError, send_packet_from_thread},
+};
+
+/// How much messages are kept in the log
+const LOG_CAPACITY: usize = 256;
+
+/// Max length of a chat message or a command
+const MAX_MESSAGE_LENGTH: usize = 256;
+
+/// How much signatures the server tracks for acknowledgement
+const LAST_SEEN_COUNT: usize = 20;
+
+/// Pending signed messages count above which the client must send an acknowledgement
+const MAX_PENDING_ACKNOWLEDGEMENTS: i32 = 64;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum ChatKind {
+    System {
+        /// Displayed above the hotbar instead of in the chat
+        overlay: bool,
+    },
+    /// `chat_type` is `None` when the chat type was sent inline
+    Player {
+        sender_uuid: u128,
+        chat_type: Option<i32>,
+    },
+    Disguised {
+        chat_type: Option<i32>,
+    },
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct ChatEntry {
+    pub kind: ChatKind,
+    pub sender: Option<String>,
+    pub content: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Chat {
+    log: VecDeque<ChatEntry>,
+    secure_chat_enforced: bool,
+    last_seen: LastSeenMessages,
+}
+
+impl Chat {
+    pub fn set_secure_chat_enforced(&mut self, enforced: bool) {
+        self.secure_chat_enforced = enforced;
+    }
+
+    pub fn push(&mut self, entry: ChatEntry) {
+        if self.log.len() == LOG_CAPACITY {
+            self.log.pop_front();
+        }
+        self.log.push_back(entry);
+    }
+
+    /// Messages received, from the oldest to the newest
+    #[allow(dead_code)]
+    pub fn messages(&self) -> impl Iterator<Item = &ChatEntry> {
+        self.log.iter()
+    }
+
+    /// Track a received player message.
+    /// Return the offset to acknowledge if the client has to send an acknowledgement now
+    pub fn message_received(&mut self, signature: Option<MessageSignature>) -> Option<i32> {
+        let signature = signature?;
+        self.last_seen.add_pending(signature);
+        if self.last_seen.offset > MAX_PENDING_ACKNOWLEDGEMENTS {
+            Some(self.last_seen.take_offset())
+        } else {
+            None
+        }
+    }
+
+    /// Send an unsigned chat message
+    #[allow(dead_code)]
+    pub fn say(&mut self, message: &str) -> Result<(), ReceiveError> {
+        if self.secure_chat_enforced {
+            return Err(GameError::SecureChatEnforced.into());
+        }
+        check_length(message)?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as i64);
+        let (offset, acknowledged) = self.last_seen.generate_update();
+
+        send_packet_from_thread(ChatMessage {
+            message: message.to_string(),
+            timestamp,
+            salt: 0,
+            signature: None,
+            message_count: VarInt(offset),
+            acknowledged,
+            checksum: 0,
+        })?;
+        Ok(())
+    }
+
+    /// Send an unsigned command, `command` shouldn't start with a `/`
+    #[allow(dead_code)]
+    pub fn command(&mut self, command: &str) -> Result<(), ReceiveError> {
+        check_length(command)?;
+
+        send_packet_from_thread(ChatCommand {
+            command: command.to_string(),
+        })?;
+        Ok(())
+    }
+}
+
+fn check_length(message: &str) -> Result<(), GameError> {
+    let len = message.chars().count();
+    if len > MAX_MESSAGE_LENGTH {
+        return Err(GameError::MessageTooLong(len));
+    }
+    Ok(())
+}
+
+/// Mirror of the server's view of the signed messages we've seen
+#[derive(Debug)]
+struct LastSeenMessages {
+    /// Ring buffer of the last seen signatures
+    entries: [Option<MessageSignature>; LAST_SEEN_COUNT],
+    tail: usize,
+    /// Messages received since the last acknowledgement
+    offset: i32,
+}
+
+impl Default for LastSeenMessages {
+    fn default() -> Self {
+        Self {
+            entries: [None; LAST_SEEN_COUNT],
+            tail: 0,
+            offset: 0,
+        }
+    }
+}
+
+impl LastSeenMessages {
+    fn add_pending(&mut self, signature: MessageSignature) {
+        self.entries[self.tail] = Some(signature);
+        self.tail = (self.tail + 1) % LAST_SEEN_COUNT;
+        self.offset += 1;
+    }
+
+    fn take_offset(&mut self) -> i32 {
+        std::mem::take(&mut self.offset)
+    }
+
+    /// Return the offset and the acknowledged bitset to send with a message
+    fn generate_update(&mut self) -> (i32, [u8; LAST_SEEN_COUNT.div_ceil(8)]) {
+        let mut acknowledged = [0; LAST_SEEN_COUNT.div_ceil(8)];
+        for i in 0..LAST_SEEN_COUNT {
+            if self.entries[(self.tail + i) % LAST_SEEN_COUNT].is_some() {
+                acknowledged[i / 8] |= 1 << (i % 8);
+            }
+        }
+        (self.take_offset(), acknowledged)
+    }
+}
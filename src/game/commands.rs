@@ -0,0 +1,589 @@
+use std::{
+    collections::HashMap,
+    sync::mpsc::{Receiver, Sender, channel},
+};
+
+use thiserror::Error;
+
+use crate::{
+    data::{DataStream, Deserialize, DeserializeError, SerializeError},
+    datatypes::{TextComponent, VarInt},
+    packets::{CommandSuggestionsRequest, send_packet_from_thread},
+};
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CommandError {
+    #[error("The server didn't send any command tree")]
+    NoTree,
+    #[error("Unknown command {0:?}")]
+    UnknownCommand(String),
+    #[error("Incomplete command, expected one of: {0:?}")]
+    Incomplete(Vec<String>),
+    #[error("Invalid argument {argument} at position {position}")]
+    InvalidArgument { argument: String, position: usize },
+    #[error("Unexpected {0:?}")]
+    TrailingData(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringKind {
+    SingleWord,
+    QuotablePhrase,
+    GreedyPhrase,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandParser {
+    Bool,
+    Float {
+        min: Option<f32>,
+        max: Option<f32>,
+    },
+    Double {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    Integer {
+        min: Option<i32>,
+        max: Option<i32>,
+    },
+    Long {
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    String(StringKind),
+    Entity {
+        single: bool,
+        players_only: bool,
+    },
+    ScoreHolder {
+        multiple: bool,
+    },
+    Time {
+        min: i32,
+    },
+    ResourceOrTag {
+        registry: String,
+    },
+    ResourceOrTagKey {
+        registry: String,
+    },
+    Resource {
+        registry: String,
+    },
+    ResourceKey {
+        registry: String,
+    },
+    ResourceSelector {
+        registry: String,
+    },
+    /// Parser without properties, identified by its name (e.g. `minecraft:block_pos`)
+    Other(&'static str),
+}
+
+/// Names of the argument parsers, indexed by their protocol ID
+const PARSERS: &[&str] = &[
+    "brigadier:bool",
+    "brigadier:float",
+    "brigadier:double",
+    "brigadier:integer",
+    "brigadier:long",
+    "brigadier:string",
+    "minecraft:entity",
+    "minecraft:game_profile",
+    "minecraft:block_pos",
+    "minecraft:column_pos",
+    "minecraft:vec3",
+    "minecraft:vec2",
+    "minecraft:block_state",
+    "minecraft:block_predicate",
+    "minecraft:item_stack",
+    "minecraft:item_predicate",
+    "minecraft:color",
+    "minecraft:hex_color",
+    "minecraft:component",
+    "minecraft:style",
+    "minecraft:message",
+    "minecraft:nbt_compound_tag",
+    "minecraft:nbt_tag",
+    "minecraft:nbt_path",
+    "minecraft:objective",
+    "minecraft:objective_criteria",
+    "minecraft:operation",
+    "minecraft:particle",
+    "minecraft:angle",
+    "minecraft:rotation",
+    "minecraft:scoreboard_slot",
+    "minecraft:score_holder",
+    "minecraft:swizzle",
+    "minecraft:team",
+    "minecraft:item_slot",
+    "minecraft:item_slots",
+    "minecraft:resource_location",
+    "minecraft:function",
+    "minecraft:entity_anchor",
+    "minecraft:int_range",
+    "minecraft:float_range",
+    "minecraft:dimension",
+    "minecraft:gamemode",
+    "minecraft:time",
+    "minecraft:resource_or_tag",
+    "minecraft:resource_or_tag_key",
+    "minecraft:resource",
+    "minecraft:resource_key",
+    "minecraft:resource_selector",
+    "minecraft:template_mirror",
+    "minecraft:template_rotation",
+    "minecraft:heightmap",
+    "minecraft:loot_table",
+    "minecraft:loot_predicate",
+    "minecraft:loot_modifier",
+    "minecraft:dialog",
+    "minecraft:uuid",
+];
+
+impl Deserialize for CommandParser {
+    fn deserialize(stream: &mut DataStream) -> Result<Self, DeserializeError> {
+        let id = VarInt::deserialize(stream)?.0;
+        let name = usize::try_from(id)
+            .ok()
+            .and_then(|id| PARSERS.get(id))
+            .ok_or_else(|| {
+                DeserializeError::MalformedPacket(format!("Unknown command parser {}", id))
+            })?;
+
+        macro_rules! range {
+            ($variant: ident, $T: ty) => {{
+                let flags = u8::deserialize(stream)?;
+                let min = if flags & 1 != 0 {
+                    Some(<$T>::deserialize(stream)?)
+                } else {
+                    None
+                };
+                let max = if flags & 2 != 0 {
+                    Some(<$T>::deserialize(stream)?)
+                } else {
+                    None
+                };
+                Self::$variant { min, max }
+            }};
+        }
+
+        let parser = match *name {
+            "brigadier:bool" => Self::Bool,
+            "brigadier:float" => range!(Float, f32),
+            "brigadier:double" => range!(Double, f64),
+            "brigadier:integer" => range!(Integer, i32),
+            "brigadier:long" => range!(Long, i64),
+            "brigadier:string" => Self::String(match VarInt::deserialize(stream)?.0 {
+                0 => StringKind::SingleWord,
+                1 => StringKind::QuotablePhrase,
+                2 => StringKind::GreedyPhrase,
+                k => {
+                    return Err(DeserializeError::MalformedPacket(format!(
+                        "Invalid string parser kind {}",
+                        k
+                    )));
+                }
+            }),
+            "minecraft:entity" => {
+                let flags = u8::deserialize(stream)?;
+                Self::Entity {
+                    single: flags & 1 != 0,
+                    players_only: flags & 2 != 0,
+                }
+            }
+            "minecraft:score_holder" => Self::ScoreHolder {
+                multiple: u8::deserialize(stream)? & 1 != 0,
+            },
+            "minecraft:time" => Self::Time {
+                min: i32::deserialize(stream)?,
+            },
+            "minecraft:resource_or_tag" => Self::ResourceOrTag {
+                registry: String::deserialize(stream)?,
+            },
+            "minecraft:resource_or_tag_key" => Self::ResourceOrTagKey {
+                registry: String::deserialize(stream)?,
+            },
+            "minecraft:resource" => Self::Resource {
+                registry: String::deserialize(stream)?,
+            },
+            "minecraft:resource_key" => Self::ResourceKey {
+                registry: String::deserialize(stream)?,
+            },
+            "minecraft:resource_selector" => Self::ResourceSelector {
+                registry: String::deserialize(stream)?,
+            },
+            name => Self::Other(name),
+        };
+        Ok(parser)
+    }
+}
+
+impl CommandParser {
+    /// Try to parse the argument at the beginning of `input`.
+    /// Return the number of bytes consumed
+    fn parse(&self, input: &str) -> Option<usize> {
+        fn number<T: std::str::FromStr + PartialOrd>(
+            input: &str,
+            min: Option<T>,
+            max: Option<T>,
+        ) -> Option<usize> {
+            let token = read_token(input)?;
+            let value: T = token.parse().ok()?;
+            if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+                return None;
+            }
+            Some(token.len())
+        }
+
+        match self {
+            Self::Bool => {
+                let token = read_token(input)?;
+                matches!(token, "true" | "false").then_some(token.len())
+            }
+            Self::Float { min, max } => number(input, *min, *max),
+            Self::Double { min, max } => number(input, *min, *max),
+            Self::Integer { min, max } => number(input, *min, *max),
+            Self::Long { min, max } => number(input, *min, *max),
+            Self::Time { .. } => {
+                let token = read_token(input)?;
+                let number = token.trim_end_matches(['d', 's', 't']);
+                number.parse::<f32>().ok()?;
+                Some(token.len())
+            }
+            Self::String(StringKind::SingleWord) => {
+                let token = read_token(input)?;
+                let valid = token
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "_-.+".contains(c));
+                valid.then_some(token.len())
+            }
+            Self::String(StringKind::QuotablePhrase) => read_token(input).map(str::len),
+            Self::String(StringKind::GreedyPhrase) | Self::Other("minecraft:message") => {
+                (!input.is_empty()).then_some(input.len())
+            }
+            Self::Other(_) => read_tokens(input, self.components()),
+            _ => read_token(input).map(str::len),
+        }
+    }
+
+    /// Number of space separated components of the argument
+    fn components(&self) -> usize {
+        match self {
+            Self::Other("minecraft:block_pos" | "minecraft:vec3") => 3,
+            Self::Other("minecraft:column_pos" | "minecraft:vec2" | "minecraft:rotation") => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Read one token, keeping together what's between quotes or brackets
+fn read_token(input: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => (),
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '{' | '[' | '(') => depth += 1,
+            (None, '}' | ']' | ')') => depth = depth.checked_sub(1)?,
+            (None, ' ') if depth == 0 => return (i > 0).then(|| &input[..i]),
+            _ => (),
+        }
+    }
+
+    (!input.is_empty() && depth == 0 && quote.is_none()).then_some(input)
+}
+
+/// Read `count` tokens separated by spaces. Return the number of bytes consumed
+fn read_tokens(input: &str, count: usize) -> Option<usize> {
+    let mut consumed = 0;
+    for i in 0..count {
+        if i > 0 {
+            consumed += input[consumed..].starts_with(' ').then_some(1)?;
+        }
+        consumed += read_token(&input[consumed..])?.len();
+    }
+    Some(consumed)
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum CommandNodeKind {
+    Root,
+    Literal(String),
+    Argument {
+        name: String,
+        parser: CommandParser,
+        suggestions: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct CommandNode {
+    pub kind: CommandNodeKind,
+    pub executable: bool,
+    pub children: Vec<usize>,
+    pub redirect: Option<usize>,
+}
+
+impl CommandNode {
+    /// Name displayed to the user: the literal itself or `<name>` for arguments
+    pub fn display_name(&self) -> String {
+        match &self.kind {
+            CommandNodeKind::Root => String::new(),
+            CommandNodeKind::Literal(name) => name.clone(),
+            CommandNodeKind::Argument { name, .. } => format!("<{}>", name),
+        }
+    }
+}
+
+impl Deserialize for CommandNode {
+    fn deserialize(stream: &mut DataStream) -> Result<Self, DeserializeError> {
+        let flags = u8::deserialize(stream)?;
+        let children = Vec::<VarInt>::deserialize(stream)?
+            .into_iter()
+            .map(|v| v.0 as usize)
+            .collect();
+        let redirect = if flags & 0x08 != 0 {
+            Some(VarInt::deserialize(stream)?.0 as usize)
+        } else {
+            None
+        };
+
+        let kind = match flags & 0x03 {
+            0 => CommandNodeKind::Root,
+            1 => CommandNodeKind::Literal(String::deserialize(stream)?),
+            2 => {
+                let name = String::deserialize(stream)?;
+                let parser = CommandParser::deserialize(stream)?;
+                let suggestions = if flags & 0x10 != 0 {
+                    Some(String::deserialize(stream)?)
+                } else {
+                    None
+                };
+                CommandNodeKind::Argument {
+                    name,
+                    parser,
+                    suggestions,
+                }
+            }
+            k => {
+                return Err(DeserializeError::MalformedPacket(format!(
+                    "Invalid command node type {}",
+                    k
+                )));
+            }
+        };
+
+        Ok(Self {
+            kind,
+            executable: flags & 0x04 != 0,
+            children,
+            redirect,
+        })
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct Suggestions {
+    /// Start of the replaced text in the request
+    pub start: usize,
+    pub length: usize,
+    pub matches: Vec<(String, Option<TextComponent>)>,
+}
+
+#[derive(Debug, Default)]
+pub struct Commands {
+    nodes: Vec<CommandNode>,
+    root: usize,
+    next_transaction_id: i32,
+    pending_suggestions: HashMap<i32, Sender<Suggestions>>,
+}
+
+impl Commands {
+    pub fn set_tree(&mut self, nodes: Vec<CommandNode>, root: usize) {
+        self.nodes = nodes;
+        self.root = root;
+    }
+
+    #[allow(dead_code)]
+    pub fn node(&self, idx: usize) -> Option<&CommandNode> {
+        self.nodes.get(idx)
+    }
+
+    /// Children of a node, following redirections.
+    /// Literals come first as brigadier tries them before arguments
+    fn children(&self, idx: usize) -> Vec<usize> {
+        let node = &self.nodes[idx];
+        let mut children = match node.redirect {
+            Some(redirect) => self.nodes[redirect].children.clone(),
+            None => node.children.clone(),
+        };
+        children.sort_by_key(|&c| !matches!(self.nodes[c].kind, CommandNodeKind::Literal(_)));
+        children
+    }
+
+    fn children_names(&self, idx: usize) -> Vec<String> {
+        self.children(idx)
+            .into_iter()
+            .map(|c| self.nodes[c].display_name())
+            .collect()
+    }
+
+    /// Match the children of `idx` against the beginning of `input`.
+    /// Return the matched children with the number of bytes they consumed
+    fn match_children(&self, idx: usize, input: &str) -> Vec<(usize, usize)> {
+        self.children(idx)
+            .into_iter()
+            .filter_map(|child| {
+                let len = match &self.nodes[child].kind {
+                    CommandNodeKind::Root => None,
+                    CommandNodeKind::Literal(name) => {
+                        (read_token(input) == Some(name.as_str())).then_some(name.len())
+                    }
+                    CommandNodeKind::Argument { parser, .. } => parser.parse(input),
+                }?;
+                // An argument must be followed by a separator or the end of the command
+                match input[len..].chars().next() {
+                    None | Some(' ') => Some((child, len)),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Return the input remaining after node `idx` matched up to `position`, without the separator
+    fn next_input<'a>(&self, idx: usize, command: &'a str, position: usize) -> (&'a str, usize) {
+        if idx == self.root {
+            (&command[position..], position)
+        } else {
+            (&command[position + 1..], position + 1)
+        }
+    }
+
+    /// Check locally if the server would accept `command` (without the leading `/`)
+    #[allow(dead_code)]
+    pub fn validate(&self, command: &str) -> Result<(), CommandError> {
+        if self.nodes.is_empty() {
+            return Err(CommandError::NoTree);
+        }
+
+        self.validate_from(self.root, command, 0)
+            .map_err(|(_, e)| e)
+    }
+
+    /// Continue the validation after node `idx` matched up to `position`.
+    /// Errors are returned with the position where they happened
+    fn validate_from(
+        &self,
+        idx: usize,
+        command: &str,
+        position: usize,
+    ) -> Result<(), (usize, CommandError)> {
+        if position == command.len() {
+            if self.nodes[idx].executable {
+                return Ok(());
+            }
+            return Err((position, CommandError::Incomplete(self.children_names(idx))));
+        }
+
+        let (rest, position) = self.next_input(idx, command, position);
+        let matches = self.match_children(idx, rest);
+        if matches.is_empty() {
+            let error = if idx == self.root {
+                let name = read_token(rest).unwrap_or(rest);
+                CommandError::UnknownCommand(name.to_string())
+            } else if self.children(idx).is_empty() {
+                CommandError::TrailingData(rest.to_string())
+            } else {
+                CommandError::InvalidArgument {
+                    argument: self.children_names(idx).join("|"),
+                    position,
+                }
+            };
+            return Err((position, error));
+        }
+
+        let mut best_error: Option<(usize, CommandError)> = None;
+        for (child, len) in matches {
+            match self.validate_from(child, command, position + len) {
+                Ok(()) => return Ok(()),
+                Err((p, e)) => {
+                    if best_error.as_ref().is_none_or(|(best, _)| p > *best) {
+                        best_error = Some((p, e));
+                    }
+                }
+            }
+        }
+        Err(best_error.expect("There was at least one match"))
+    }
+
+    /// List the sub-commands and arguments that can follow `partial`
+    /// (complete words only, without the leading `/`)
+    #[allow(dead_code)]
+    pub fn next_arguments(&self, partial: &str) -> Vec<String> {
+        if self.nodes.is_empty() {
+            return Vec::new();
+        }
+
+        let mut reached = Vec::new();
+        self.walk(self.root, partial.trim_end(), 0, &mut reached);
+
+        let mut names: Vec<String> = reached
+            .into_iter()
+            .flat_map(|idx| self.children_names(idx))
+            .collect();
+        names.dedup();
+        names
+    }
+
+    /// Collect the nodes reached once the whole `command` is consumed
+    fn walk(&self, idx: usize, command: &str, position: usize, reached: &mut Vec<usize>) {
+        if position == command.len() {
+            reached.push(idx);
+            return;
+        }
+
+        let (rest, position) = self.next_input(idx, command, position);
+        for (child, len) in self.match_children(idx, rest) {
+            self.walk(child, command, position + len, reached);
+        }
+    }
+
+    /// Ask the server for completions of `text` (without the leading `/`).
+    /// The response will be sent through the returned channel
+    #[allow(dead_code)]
+    pub fn request_suggestions(
+        &mut self,
+        text: &str,
+    ) -> Result<Receiver<Suggestions>, SerializeError> {
+        let transaction_id = self.next_transaction_id;
+        self.next_transaction_id = self.next_transaction_id.wrapping_add(1);
+
+        let (sender, receiver) = channel();
+        self.pending_suggestions.insert(transaction_id, sender);
+
+        send_packet_from_thread(CommandSuggestionsRequest {
+            transaction_id: VarInt(transaction_id),
+            text: format!("/{}", text),
+        })?;
+
+        Ok(receiver)
+    }
+
+    pub fn suggestions_received(&mut self, transaction_id: i32, suggestions: Suggestions) {
+        if let Some(sender) = self.pending_suggestions.remove(&transaction_id) {
+            // The requester may have given up waiting
+            let _ = sender.send(suggestions);
+        }
+    }
+}
@@ -0,0 +1,427 @@
+use macros::Deserialize;
+
+use crate::{
+    datatypes::{BlockPos, TextComponent, VarInt},
+    game::{IdSet, Slot},
+    nbt::Nbt,
+    packets::PlayerProperty,
+};
+
+/// A data component of an item stack, by ID in the `minecraft:data_component_type` registry.
+///
+/// Components are not prefixed by their length, so every component of the protocol has to be
+/// decoded, even the ones the client doesn't use.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[repr(u32)]
+#[enum_repr(VarInt)]
+pub enum StructuredComponent {
+    CustomData(Nbt) = 0,
+    MaxStackSize(VarInt) = 1,
+    MaxDamage(VarInt) = 2,
+    Damage(VarInt) = 3,
+    Unbreakable = 4,
+    CustomName(TextComponent) = 5,
+    ItemName(TextComponent) = 6,
+    ItemModel(String) = 7,
+    Lore(Vec<TextComponent>) = 8,
+    Rarity(Rarity) = 9,
+    Enchantments(Vec<Enchantment>) = 10,
+    CanPlaceOn(Vec<BlockPredicate>) = 11,
+    CanBreak(Vec<BlockPredicate>) = 12,
+    AttributeModifiers(Vec<ItemAttributeModifier>) = 13,
+    CustomModelData {
+        floats: Vec<f32>,
+        flags: Vec<bool>,
+        strings: Vec<String>,
+        colors: Vec<i32>,
+    } = 14,
+    TooltipDisplay {
+        hide_tooltip: bool,
+        hidden_components: Vec<VarInt>,
+    } = 15,
+    RepairCost(VarInt) = 16,
+    CreativeSlotLock = 17,
+    EnchantmentGlintOverride(bool) = 18,
+    IntangibleProjectile(Nbt) = 19,
+    Food {
+        nutrition: VarInt,
+        saturation_modifier: f32,
+        can_always_eat: bool,
+    } = 20,
+    Consumable {
+        consume_seconds: f32,
+        animation: VarInt,
+        sound: SoundEvent,
+        has_consume_particles: bool,
+        effects: Vec<ConsumeEffect>,
+    } = 21,
+    UseRemainder(Box<Slot>) = 22,
+    UseCooldown {
+        seconds: f32,
+        cooldown_group: Option<String>,
+    } = 23,
+    DamageResistant(String) = 24,
+    Tool(Tool) = 25,
+    Weapon {
+        item_damage_per_attack: VarInt,
+        disable_blocking_for_seconds: f32,
+    } = 26,
+    Enchantable(VarInt) = 27,
+    Equippable(Equippable) = 28,
+    Repairable(IdSet) = 29,
+    Glider = 30,
+    TooltipStyle(String) = 31,
+    DeathProtection(Vec<ConsumeEffect>) = 32,
+    BlocksAttacks(BlocksAttacks) = 33,
+    StoredEnchantments(Vec<Enchantment>) = 34,
+    DyedColor(i32) = 35,
+    MapColor(i32) = 36,
+    MapId(VarInt) = 37,
+    MapDecorations(Nbt) = 38,
+    MapPostProcessing(VarInt) = 39,
+    ChargedProjectiles(Vec<Slot>) = 40,
+    BundleContents(Vec<Slot>) = 41,
+    PotionContents {
+        potion: Option<VarInt>,
+        custom_color: Option<i32>,
+        custom_effects: Vec<PotionEffect>,
+        custom_name: Option<String>,
+    } = 42,
+    PotionDurationScale(f32) = 43,
+    SuspiciousStewEffects(Vec<(VarInt, VarInt)>) = 44,
+    WritableBookContent(Vec<(String, Option<String>)>) = 45,
+    WrittenBookContent {
+        raw_title: String,
+        filtered_title: Option<String>,
+        author: String,
+        generation: VarInt,
+        pages: Vec<(TextComponent, Option<TextComponent>)>,
+        resolved: bool,
+    } = 46,
+    Trim {
+        material: IdOr<TrimMaterial>,
+        pattern: IdOr<TrimPattern>,
+    } = 47,
+    DebugStickState(Nbt) = 48,
+    EntityData(Nbt) = 49,
+    BucketEntityData(Nbt) = 50,
+    BlockEntityData(Nbt) = 51,
+    Instrument(EitherHolder<IdOr<Instrument>>) = 52,
+    ProvidesTrimMaterial(EitherHolder<IdOr<TrimMaterial>>) = 53,
+    OminousBottleAmplifier(VarInt) = 54,
+    JukeboxPlayable(EitherHolder<IdOr<JukeboxSong>>) = 55,
+    ProvidesBannerPatterns(String) = 56,
+    Recipes(Nbt) = 57,
+    LodestoneTracker {
+        target: Option<(String, BlockPos)>,
+        tracked: bool,
+    } = 58,
+    FireworkExplosion(FireworkExplosion) = 59,
+    Fireworks {
+        flight_duration: VarInt,
+        explosions: Vec<FireworkExplosion>,
+    } = 60,
+    Profile {
+        name: Option<String>,
+        uuid: Option<u128>,
+        properties: Vec<PlayerProperty>,
+    } = 61,
+    NoteBlockSound(String) = 62,
+    /// Patterns with their dye color
+    BannerPatterns(Vec<(IdOr<BannerPattern>, VarInt)>) = 63,
+    BaseColor(VarInt) = 64,
+    PotDecorations(Vec<VarInt>) = 65,
+    Container(Vec<Slot>) = 66,
+    BlockState(Vec<(String, String)>) = 67,
+    Bees(Vec<Bee>) = 68,
+    Lock(Nbt) = 69,
+    ContainerLoot(Nbt) = 70,
+    BreakSound(SoundEvent) = 71,
+    // Variants of the entities spawned by the item, by registry ID or enum value
+    VillagerVariant(VarInt) = 72,
+    WolfVariant(VarInt) = 73,
+    WolfSoundVariant(VarInt) = 74,
+    WolfCollar(VarInt) = 75,
+    FoxVariant(VarInt) = 76,
+    SalmonSize(VarInt) = 77,
+    ParrotVariant(VarInt) = 78,
+    TropicalFishPattern(VarInt) = 79,
+    TropicalFishBaseColor(VarInt) = 80,
+    TropicalFishPatternColor(VarInt) = 81,
+    MooshroomVariant(VarInt) = 82,
+    RabbitVariant(VarInt) = 83,
+    PigVariant(VarInt) = 84,
+    CowVariant(VarInt) = 85,
+    ChickenVariant(EitherHolder<VarInt>) = 86,
+    FrogVariant(VarInt) = 87,
+    HorseVariant(VarInt) = 88,
+    PaintingVariant(IdOr<PaintingVariant>) = 89,
+    LlamaVariant(VarInt) = 90,
+    AxolotlVariant(VarInt) = 91,
+    CatVariant(VarInt) = 92,
+    CatCollar(VarInt) = 93,
+    SheepColor(VarInt) = 94,
+    ShulkerColor(VarInt) = 95,
+}
+
+/// An entry of a registry, by ID or given inline
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdOr<T> {
+    Registry(i32),
+    Inline(T),
+}
+
+/// An entry of a registry, either resolved by the server or by name
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[enum_repr(bool)]
+pub enum EitherHolder<T> {
+    Key(String),
+    Holder(T),
+}
+
+/// Deserialized by hand, as the derived bounds would be recursive with [`StructuredComponent`]
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockPredicate {
+    pub blocks: Option<IdSet>,
+    pub properties: Option<Vec<(String, PropertyValueMatcher)>>,
+    pub nbt: Option<Nbt>,
+    /// Components the block entity must have, with these values
+    pub exact_components: Vec<StructuredComponent>,
+    /// Predicates on components, by component type
+    pub partial_components: Vec<(VarInt, Nbt)>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[enum_repr(bool)]
+pub enum PropertyValueMatcher {
+    Range {
+        min: Option<String>,
+        max: Option<String>,
+    },
+    Exact(String),
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemAttributeModifier {
+    /// ID in the `minecraft:attribute` registry
+    pub attribute: VarInt,
+    pub id: String,
+    pub amount: f64,
+    pub operation: VarInt,
+    /// Equipment slot group the modifier applies in
+    pub slot: VarInt,
+    pub display: AttributeModifierDisplay,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[repr(u32)]
+#[enum_repr(VarInt)]
+pub enum AttributeModifierDisplay {
+    Default = 0,
+    Hidden,
+    Override(TextComponent),
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Equippable {
+    pub slot: VarInt,
+    pub equip_sound: SoundEvent,
+    pub asset_id: Option<String>,
+    pub camera_overlay: Option<String>,
+    /// Entities of the `minecraft:entity_type` registry which can wear the item
+    pub allowed_entities: Option<IdSet>,
+    pub dispensable: bool,
+    pub swappable: bool,
+    pub damage_on_hurt: bool,
+    pub equip_on_interact: bool,
+    pub can_be_sheared: bool,
+    pub shearing_sound: SoundEvent,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BlocksAttacks {
+    pub block_delay_seconds: f32,
+    pub disable_cooldown_scale: f32,
+    pub damage_reductions: Vec<DamageReduction>,
+    pub item_damage_threshold: f32,
+    pub item_damage_base: f32,
+    pub item_damage_factor: f32,
+    /// Tag of damage types which aren't blocked
+    pub bypassed_by: Option<String>,
+    pub block_sound: Option<SoundEvent>,
+    pub disable_sound: Option<SoundEvent>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DamageReduction {
+    pub horizontal_blocking_angle: f32,
+    /// Damage types of the `minecraft:damage_type` registry, all of them if absent
+    pub damage_types: Option<IdSet>,
+    pub base: f32,
+    pub factor: f32,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TrimMaterial {
+    pub asset_suffix: String,
+    /// Suffixes used instead for some equipment assets
+    pub asset_overrides: Vec<(String, String)>,
+    pub description: TextComponent,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TrimPattern {
+    pub asset_id: String,
+    pub description: TextComponent,
+    pub decal: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Instrument {
+    pub sound: SoundEvent,
+    pub use_duration: f32,
+    pub range: f32,
+    pub description: TextComponent,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct JukeboxSong {
+    pub sound: SoundEvent,
+    pub description: TextComponent,
+    pub length_in_seconds: f32,
+    pub comparator_output: VarInt,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BannerPattern {
+    pub asset_id: String,
+    pub translation_key: String,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PaintingVariant {
+    pub width: VarInt,
+    pub height: VarInt,
+    pub asset_id: String,
+    pub title: Option<TextComponent>,
+    pub author: Option<TextComponent>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[enum_repr(VarInt)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Enchantment {
+    /// ID in the `minecraft:enchantment` registry
+    pub id: VarInt,
+    pub level: VarInt,
+}
+
+/// A sound, either from the `minecraft:sound_event` registry or given inline
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SoundEvent {
+    Registry(i32),
+    Inline {
+        name: String,
+        fixed_range: Option<f32>,
+    },
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PotionEffect {
+    /// ID in the `minecraft:mob_effect` registry
+    pub id: VarInt,
+    pub details: PotionEffectDetails,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PotionEffectDetails {
+    pub amplifier: VarInt,
+    /// In ticks, -1 for infinite
+    pub duration: VarInt,
+    pub ambient: bool,
+    pub show_particles: bool,
+    pub show_icon: bool,
+    /// Effect with a lower amplifier, applied when this one ends
+    pub hidden_effect: Option<Box<PotionEffectDetails>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[repr(u32)]
+#[enum_repr(VarInt)]
+pub enum ConsumeEffect {
+    ApplyEffects {
+        effects: Vec<PotionEffect>,
+        probability: f32,
+    } = 0,
+    RemoveEffects(IdSet),
+    ClearAllEffects,
+    TeleportRandomly {
+        diameter: f32,
+    },
+    PlaySound(SoundEvent),
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Tool {
+    pub rules: Vec<ToolRule>,
+    pub default_mining_speed: f32,
+    pub damage_per_block: VarInt,
+    pub can_destroy_blocks_in_creative: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ToolRule {
+    /// Blocks of the `minecraft:block` registry
+    pub blocks: IdSet,
+    pub speed: Option<f32>,
+    pub correct_for_drops: Option<bool>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FireworkExplosion {
+    pub shape: VarInt,
+    pub colors: Vec<i32>,
+    pub fade_colors: Vec<i32>,
+    pub has_trail: bool,
+    pub has_twinkle: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Bee {
+    pub entity_data: Nbt,
+    pub ticks_in_hive: VarInt,
+    pub min_ticks_in_hive: VarInt,
+}
@@ -0,0 +1,332 @@
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+use log::warn;
+
+use crate::{
+    datatypes::{BlockPos, VarInt},
+    game::{
+        ARMOR_SLOTS, BlockFace, BlockState, Game, GameError, GameMode, Hand, Slot, Tags, Vec3d,
+        Vec3i, player::effects, tags::BLOCK_REGISTRY,
+    },
+    packets::{DigStatus, PlayerDigging, ReceiveError, SwingArm, send_packet_from_thread},
+};
+
+/// Hardness used for the blocks missing from `data/hardness.txt`
+const DEFAULT_HARDNESS: f32 = 1.5;
+
+#[allow(dead_code)]
+impl BlockState {
+    /// Time to break the block with the hand, -1 if it can't be broken. Blocks without a known
+    /// hardness get the one of stone.
+    pub fn hardness(self) -> f32 {
+        self.known_hardness().unwrap_or(DEFAULT_HARDNESS)
+    }
+
+    pub fn known_hardness(self) -> Option<f32> {
+        if self.is_air() {
+            return Some(0.);
+        }
+        self.block().and_then(|b| b.hardness)
+    }
+
+    /// The block drops nothing if it's not mined with the correct tool
+    pub fn requires_correct_tool(self) -> bool {
+        self.block().is_some_and(|b| b.requires_correct_tool)
+    }
+}
+
+/// Mining speed of an item on a block, and if it's the correct tool
+fn tool_speed(slot: &Slot, state: BlockState, tags: &Tags) -> (f32, bool) {
+    if let Some(tool) = slot.tool() {
+        // Tool component overridden for this stack
+        let block_id = state.block_id().unwrap_or(-1);
+        let matching = || {
+            tool.rules
+                .iter()
+                .filter(|r| tags.id_set_contains(BLOCK_REGISTRY, &r.blocks, block_id))
+        };
+        let speed = matching().find_map(|r| r.speed);
+        let correct = matching().find_map(|r| r.correct_for_drops);
+        return (
+            speed.unwrap_or(tool.default_mining_speed),
+            correct.unwrap_or(false),
+        );
+    }
+
+    let Some(tool) = slot.item().and_then(|i| i.tool()) else {
+        return (1., false);
+    };
+    let matching = || {
+        tool.rules.iter().filter(|r| {
+            r.blocks.iter().any(|b| match b.strip_prefix('#') {
+                Some(tag) => tags.block_has(tag, state),
+                None => *b == state.name(),
+            })
+        })
+    };
+    let speed = matching().find_map(|r| r.speed);
+    let correct = matching().find_map(|r| r.correct_for_drops);
+    (
+        speed.unwrap_or(tool.default_mining_speed),
+        correct.unwrap_or(false),
+    )
+}
+
+/// Level of an enchantment on a stack, 0 if it's not enchanted with it
+fn enchantment_level(game: &Game, slot: &Slot, enchantment: &str) -> i32 {
+    let Some(id) = game
+        .registries
+        .get("minecraft:enchantment")
+        .and_then(|r| r.id_of(enchantment))
+    else {
+        return 0;
+    };
+    slot.enchantments()
+        .iter()
+        .find(|e| e.id.0 == id)
+        .map_or(0, |e| e.level.0)
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigResult {
+    Broken,
+    /// The server didn't accept the breaking, the block has been restored
+    Rejected,
+    /// Digging aborted before the end, or replaced by another one
+    Aborted,
+}
+
+#[derive(Debug)]
+pub struct Digging {
+    pub pos: BlockPos,
+    face: BlockFace,
+    /// Block being broken
+    state: BlockState,
+    ticks: u32,
+    ticks_needed: u32,
+    /// Sequence of the finish packet, once sent
+    finish_sequence: Option<i32>,
+    result: Sender<DigResult>,
+}
+
+impl Game {
+    fn player_eye_position(&self) -> Vec3d {
+        const EYE_HEIGHT: f64 = 1.62;
+        let pos = self.player.entity.read().position;
+        Vec3d {
+            y: pos.y + EYE_HEIGHT,
+            ..pos
+        }
+    }
+
+    /// Damage dealt to a block each tick, the block breaks once it reaches 1
+    #[allow(dead_code)]
+    pub fn dig_progress_per_tick(&self, state: BlockState) -> f32 {
+        let hardness = state.hardness();
+        if hardness < 0. {
+            return 0.;
+        }
+        if hardness == 0. || self.player.game_mode == GameMode::Creative {
+            return 1.;
+        }
+
+        let held = self.inventory.held_item();
+        let (mut speed, correct_tool) = tool_speed(held, state, &self.tags);
+
+        if speed > 1. {
+            let efficiency = enchantment_level(self, held, "minecraft:efficiency");
+            if efficiency > 0 {
+                speed += (efficiency * efficiency + 1) as f32;
+            }
+        }
+
+        let haste = [effects::HASTE, effects::CONDUIT_POWER]
+            .into_iter()
+            .filter_map(|e| self.player.effect_amplifier(e))
+            .max();
+        if let Some(amplifier) = haste {
+            speed *= 1. + (amplifier + 1) as f32 * 0.2;
+        }
+        if let Some(amplifier) = self.player.effect_amplifier(effects::MINING_FATIGUE) {
+            speed *= match amplifier {
+                0 => 0.3,
+                1 => 0.09,
+                2 => 0.0027,
+                _ => 8.1e-4,
+            };
+        }
+
+        let eye = self.player_eye_position();
+        let eye_block = BlockPos(Vec3i {
+            x: eye.x.floor() as i32,
+            y: eye.y.floor() as i32,
+            z: eye.z.floor() as i32,
+        });
+        let in_water = self
+            .world
+            .block_at(eye_block)
+            .is_some_and(BlockState::is_water);
+        let helmet = &self.inventory.player.slots[ARMOR_SLOTS.start];
+        if in_water && enchantment_level(self, helmet, "minecraft:aqua_affinity") == 0 {
+            speed *= 0.2;
+        }
+        if !self.player.physics.on_ground {
+            speed /= 5.;
+        }
+
+        let can_harvest = correct_tool || !state.requires_correct_tool();
+        speed / hardness / if can_harvest { 30. } else { 100. }
+    }
+
+    /// Face of the block at `pos` seen by the player
+    fn facing_face(&self, pos: BlockPos) -> BlockFace {
+        let eye = self.player_eye_position();
+        let dx = eye.x - (pos.0.x as f64 + 0.5);
+        let dy = eye.y - (pos.0.y as f64 + 0.5);
+        let dz = eye.z - (pos.0.z as f64 + 0.5);
+        if dy.abs() >= dx.abs() && dy.abs() >= dz.abs() {
+            if dy > 0. {
+                BlockFace::Top
+            } else {
+                BlockFace::Bottom
+            }
+        } else if dx.abs() >= dz.abs() {
+            if dx > 0. {
+                BlockFace::East
+            } else {
+                BlockFace::West
+            }
+        } else if dz > 0. {
+            BlockFace::South
+        } else {
+            BlockFace::North
+        }
+    }
+
+    /// Start breaking the block at `pos`, the digging goes on with `tick_digging`.
+    ///
+    /// The block is removed from the world as soon as the breaking is sent, and the receiver
+    /// gets the result once the server acknowledges it.
+    #[allow(dead_code)]
+    pub fn dig(&mut self, pos: BlockPos) -> Result<Receiver<DigResult>, ReceiveError> {
+        self.abort_digging()?;
+
+        let state = self.world.block_at(pos).unwrap_or_default();
+        if state.is_air() || state.hardness() < 0. {
+            return Err(GameError::CantDig(pos).into());
+        }
+        if state.known_hardness().is_none() {
+            warn!(
+                "Unknown hardness of {}, using {}",
+                state.name(),
+                DEFAULT_HARDNESS
+            );
+        }
+
+        let progress = self.dig_progress_per_tick(state);
+        let (sender, receiver) = channel();
+        let face = self.facing_face(pos);
+        let sequence = self.player.next_sequence();
+
+        send_packet_from_thread(PlayerDigging {
+            status: DigStatus::Start,
+            location: pos,
+            face,
+            sequence: VarInt(sequence),
+        })?;
+        send_packet_from_thread(SwingArm { hand: Hand::Main })?;
+
+        let mut digging = Digging {
+            pos,
+            face,
+            state,
+            ticks: 0,
+            ticks_needed: (1. / progress).ceil() as u32,
+            finish_sequence: None,
+            result: sender,
+        };
+        if progress >= 1. {
+            // Broken instantly, the start is enough
+            self.predictions
+                .predict(&self.world, pos, BlockState::AIR, sequence);
+            digging.finish_sequence = Some(sequence);
+        }
+        self.digging = Some(digging);
+
+        Ok(receiver)
+    }
+
+    /// Called every tick, finish the breaking once enough time has passed
+    pub fn tick_digging(&mut self) -> Result<(), ReceiveError> {
+        let Some(digging) = &mut self.digging else {
+            return Ok(());
+        };
+        if digging.finish_sequence.is_some() {
+            return Ok(());
+        }
+
+        if self.world.block_at(digging.pos) != Some(digging.state) {
+            // The block changed in the meantime
+            return self.abort_digging();
+        }
+
+        digging.ticks += 1;
+        send_packet_from_thread(SwingArm { hand: Hand::Main })?;
+        if digging.ticks < digging.ticks_needed {
+            return Ok(());
+        }
+
+        let sequence = self.player.next_sequence();
+        send_packet_from_thread(PlayerDigging {
+            status: DigStatus::Finish,
+            location: digging.pos,
+            face: digging.face,
+            sequence: VarInt(sequence),
+        })?;
+        digging.finish_sequence = Some(sequence);
+        self.predictions
+            .predict(&self.world, digging.pos, BlockState::AIR, sequence);
+        Ok(())
+    }
+
+    /// Stop the current breaking, if any
+    pub fn abort_digging(&mut self) -> Result<(), ReceiveError> {
+        let Some(digging) = self.digging.take() else {
+            return Ok(());
+        };
+        if digging.finish_sequence.is_none() {
+            send_packet_from_thread(PlayerDigging {
+                status: DigStatus::Abort,
+                location: digging.pos,
+                face: digging.face,
+                sequence: VarInt(self.player.next_sequence()),
+            })?;
+        }
+        // The requester may have given up waiting
+        let _ = digging.result.send(DigResult::Aborted);
+        Ok(())
+    }
+
+    /// The server has processed the block interactions up to `sequence`, and the predictions
+    /// have been replaced by the blocks of the server
+    pub fn dig_acknowledged(&mut self, sequence: i32) {
+        let Some(digging) = &self.digging else {
+            return;
+        };
+        if digging.finish_sequence.is_none_or(|s| s > sequence) {
+            return;
+        }
+        let digging = self.digging.take().expect("Digging is some");
+
+        let result = match self.world.block_at(digging.pos) {
+            Some(state) if state == digging.state => DigResult::Rejected,
+            Some(_) => DigResult::Broken,
+            None => {
+                warn!("Block dug in an unloaded chunk ({:?})", digging.pos);
+                DigResult::Rejected
+            }
+        };
+        let _ = digging.result.send(result);
+    }
+}
@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use parking_lot::{ArcRwLockReadGuard, ArcRwLockWriteGuard, RwLock};
 
-use crate::game::{Entity, EntityId};
+use crate::game::{Entity, EntityCategory, EntityId, Game, Vec3d};
 
 pub type ReadGuard = ArcRwLockReadGuard<parking_lot::RawRwLock, Entity>;
 pub type WriteGuard = ArcRwLockWriteGuard<parking_lot::RawRwLock, Entity>;
@@ -28,8 +28,59 @@ impl Entities {
         self.take(id).map(|ptr| ptr.write_arc())
     }
 
+    pub fn remove(&self, id: EntityId) -> Option<EntityRef> {
+        self.0.write().remove(&id)
+    }
+
+    /// Keep only the entities for which `f` returns true
+    pub fn retain(&self, mut f: impl FnMut(EntityId, &EntityRef) -> bool) {
+        self.0.write().retain(|id, entity| f(*id, entity));
+    }
+
+    /// All the entities, the map isn't locked while they are used
+    pub fn all(&self) -> Vec<(EntityId, EntityRef)> {
+        let map = self.0.read();
+        map.iter().map(|(id, ptr)| (*id, Arc::clone(ptr))).collect()
+    }
+
     pub fn take(&self, id: EntityId) -> Option<EntityRef> {
         let map = self.0.read();
         map.get(&id).cloned()
     }
+
+    /// Entities within `range` blocks of `center` matching `filter`, the closest first
+    #[allow(dead_code)]
+    pub fn within(
+        &self,
+        center: Vec3d,
+        range: f64,
+        filter: impl Fn(&Entity) -> bool,
+    ) -> Vec<(EntityId, EntityRef)> {
+        let mut res: Vec<(f64, EntityId, EntityRef)> = self
+            .0
+            .read()
+            .iter()
+            .filter_map(|(id, ptr)| {
+                let entity = ptr.read();
+                let distance = (entity.position - center).length();
+                (distance <= range && filter(&entity)).then(|| (distance, *id, Arc::clone(ptr)))
+            })
+            .collect();
+        res.sort_by(|a, b| a.0.total_cmp(&b.0));
+        res.into_iter().map(|(_, id, ptr)| (id, ptr)).collect()
+    }
+}
+
+impl Game {
+    /// Entities of a category within `range` blocks of the player, the closest first
+    #[allow(dead_code)]
+    pub fn entities_near_player(
+        &self,
+        category: EntityCategory,
+        range: f64,
+    ) -> Vec<(EntityId, EntityRef)> {
+        let position = self.player.entity.read().position;
+        self.entities
+            .within(position, range, |e| e.entity_type.category() == category)
+    }
 }
@@ -1,10 +1,10 @@
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display};
 
 use macros::{Deserialize, Serialize};
 
 use crate::{
     datatypes::VarInt,
-    game::{Rotation, Vec3d},
+    game::{Aabb, EntityMetadata, EntityType, Rotation, Slot, Vec3d},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
@@ -22,18 +22,147 @@ impl From<i32> for EntityId {
     }
 }
 
+impl From<EntityId> for i32 {
+    fn from(value: EntityId) -> Self {
+        value.0
+    }
+}
+
 impl From<VarInt> for EntityId {
     fn from(value: VarInt) -> Self {
         Self(value.0)
     }
 }
 
+/// IDs in the `minecraft:attribute` registry
+#[allow(dead_code)]
+pub mod attributes {
+    pub const ARMOR: i32 = 0;
+    pub const ATTACK_DAMAGE: i32 = 2;
+    pub const ATTACK_SPEED: i32 = 4;
+    pub const BLOCK_INTERACTION_RANGE: i32 = 6;
+    pub const ENTITY_INTERACTION_RANGE: i32 = 10;
+    pub const GRAVITY: i32 = 14;
+    pub const JUMP_STRENGTH: i32 = 15;
+    pub const MAX_HEALTH: i32 = 19;
+    pub const MOVEMENT_SPEED: i32 = 22;
+    pub const SCALE: i32 = 25;
+    pub const STEP_HEIGHT: i32 = 28;
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[enum_repr(u8)]
+pub enum ModifierOperation {
+    Add,
+    MultiplyBase,
+    MultiplyTotal,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AttributeModifier {
+    pub id: String,
+    pub amount: f64,
+    pub operation: ModifierOperation,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Attribute {
+    pub base: f64,
+    pub modifiers: Vec<AttributeModifier>,
+}
+
+#[allow(dead_code)]
+impl Attribute {
+    /// Value of the attribute with its modifiers applied
+    pub fn value(&self) -> f64 {
+        let sum = |operation| {
+            self.modifiers
+                .iter()
+                .filter(move |m| m.operation == operation)
+                .map(|m| m.amount)
+        };
+        let base = self.base + sum(ModifierOperation::Add).sum::<f64>();
+        let value = base * (1. + sum(ModifierOperation::MultiplyBase).sum::<f64>());
+        sum(ModifierOperation::MultiplyTotal).fold(value, |v, amount| v * (1. + amount))
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EquipmentSlot {
+    MainHand,
+    OffHand,
+    Feet,
+    Legs,
+    Chest,
+    Head,
+    /// Armor of horses and wolves
+    Body,
+    Saddle,
+}
+
+impl EquipmentSlot {
+    pub fn from_id(id: u8) -> Option<Self> {
+        Some(match id {
+            0 => Self::MainHand,
+            1 => Self::OffHand,
+            2 => Self::Feet,
+            3 => Self::Legs,
+            4 => Self::Chest,
+            5 => Self::Head,
+            6 => Self::Body,
+            7 => Self::Saddle,
+            _ => return None,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Entity {
     #[allow(dead_code)]
     pub uuid: u128,
+    /// Estimated position, moving between the updates of the server
     pub position: Vec3d,
+    /// Last position sent by the server
+    pub server_position: Vec3d,
+    /// Ticks left to reach `server_position`
+    pub interpolation_ticks: u8,
+    pub on_ground: bool,
     pub rotation: Rotation,
+    pub head_yaw: f32,
+    /// In blocks per tick
     pub speed: Vec3d,
-    pub entity_type: i32,
+    pub entity_type: EntityType,
+    pub metadata: EntityMetadata,
+    /// Items by `EquipmentSlot`
+    pub equipment: [Slot; 8],
+    /// Attributes by ID in the `minecraft:attribute` registry
+    pub attributes: HashMap<i32, Attribute>,
+}
+
+#[allow(dead_code)]
+impl Entity {
+    pub fn equipment(&self, slot: EquipmentSlot) -> &Slot {
+        &self.equipment[slot as usize]
+    }
+
+    pub fn attribute(&self, id: i32) -> Option<f64> {
+        self.attributes.get(&id).map(Attribute::value)
+    }
+
+    /// Hitbox of the entity, from the size of its type
+    pub fn hitbox(&self) -> Aabb {
+        let info = self.entity_type.info();
+        Aabb::from_feet(self.position, info.width as f64, info.height as f64)
+    }
+
+    pub fn eye_position(&self) -> Vec3d {
+        Vec3d {
+            y: self.position.y + self.entity_type.info().eye_height as f64,
+            ..self.position
+        }
+    }
 }
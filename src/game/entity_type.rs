@@ -0,0 +1,269 @@
+use std::fmt::Display;
+
+use macros::Deserialize;
+
+use crate::datatypes::VarInt;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityCategory {
+    Hostile,
+    /// Animals and other mobs that don't attack on sight, neutral mobs included
+    Passive,
+    Player,
+    Projectile,
+    /// Dropped item
+    Item,
+    /// Boats and minecarts
+    Vehicle,
+    /// Displays, decorations, markers...
+    Misc,
+}
+
+#[derive(Debug)]
+pub struct EntityTypeInfo {
+    pub name: &'static str,
+    /// Size of the hitbox, in blocks
+    pub width: f32,
+    pub height: f32,
+    pub eye_height: f32,
+    pub category: EntityCategory,
+}
+
+/// Generate the `EntityType` enum and its table, in the order of the `minecraft:entity_type`
+/// registry
+macro_rules! entity_types {
+    ($($variant:ident = $name:literal, $width:literal, $height:literal, $eye:literal, $category:ident;)*) => {
+        /// An entity type of the `minecraft:entity_type` registry
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+        #[enum_repr(VarInt)]
+        pub enum EntityType {
+            $($variant,)*
+        }
+
+        static ENTITY_TYPES: &[EntityTypeInfo] = &[
+            $(EntityTypeInfo {
+                name: concat!("minecraft:", $name),
+                width: $width,
+                height: $height,
+                eye_height: $eye,
+                category: EntityCategory::$category,
+            },)*
+        ];
+
+        const ALL_ENTITY_TYPES: &[EntityType] = &[$(EntityType::$variant,)*];
+    };
+}
+
+// The sizes are those of the adults, slimes and magma cubes are the smallest ones
+entity_types! {
+    AcaciaBoat = "acacia_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    AcaciaChestBoat = "acacia_chest_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    Allay = "allay", 0.35, 0.6, 0.36, Passive;
+    AreaEffectCloud = "area_effect_cloud", 6.0, 0.5, 0.425, Misc;
+    Armadillo = "armadillo", 0.7, 0.65, 0.26, Passive;
+    ArmorStand = "armor_stand", 0.5, 1.975, 1.7775, Misc;
+    Arrow = "arrow", 0.5, 0.5, 0.13, Projectile;
+    Axolotl = "axolotl", 0.75, 0.42, 0.2751, Passive;
+    BambooChestRaft = "bamboo_chest_raft", 1.375, 0.5625, 0.5625, Vehicle;
+    BambooRaft = "bamboo_raft", 1.375, 0.5625, 0.5625, Vehicle;
+    Bat = "bat", 0.5, 0.9, 0.45, Passive;
+    Bee = "bee", 0.7, 0.6, 0.3, Passive;
+    BirchBoat = "birch_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    BirchChestBoat = "birch_chest_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    Blaze = "blaze", 0.6, 1.8, 1.53, Hostile;
+    BlockDisplay = "block_display", 0.0, 0.0, 0.0, Misc;
+    Bogged = "bogged", 0.6, 1.99, 1.74, Hostile;
+    Breeze = "breeze", 0.6, 1.77, 1.3452, Hostile;
+    BreezeWindCharge = "breeze_wind_charge", 0.3125, 0.3125, 0.0, Projectile;
+    Camel = "camel", 1.7, 2.375, 2.275, Passive;
+    Cat = "cat", 0.6, 0.7, 0.35, Passive;
+    CaveSpider = "cave_spider", 0.7, 0.5, 0.45, Hostile;
+    CherryBoat = "cherry_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    CherryChestBoat = "cherry_chest_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    ChestMinecart = "chest_minecart", 0.98, 0.7, 0.595, Vehicle;
+    Chicken = "chicken", 0.4, 0.7, 0.644, Passive;
+    Cod = "cod", 0.5, 0.3, 0.195, Passive;
+    CommandBlockMinecart = "command_block_minecart", 0.98, 0.7, 0.595, Vehicle;
+    Cow = "cow", 0.9, 1.4, 1.3, Passive;
+    Creaking = "creaking", 0.9, 2.7, 2.3, Hostile;
+    Creeper = "creeper", 0.6, 1.7, 1.445, Hostile;
+    DarkOakBoat = "dark_oak_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    DarkOakChestBoat = "dark_oak_chest_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    Dolphin = "dolphin", 0.9, 0.6, 0.3, Passive;
+    Donkey = "donkey", 1.3964844, 1.5, 1.425, Passive;
+    DragonFireball = "dragon_fireball", 1.0, 1.0, 0.85, Projectile;
+    Drowned = "drowned", 0.6, 1.95, 1.74, Hostile;
+    Egg = "egg", 0.25, 0.25, 0.2125, Projectile;
+    ElderGuardian = "elder_guardian", 1.9975, 1.9975, 0.99875, Hostile;
+    Enderman = "enderman", 0.6, 2.9, 2.55, Hostile;
+    Endermite = "endermite", 0.4, 0.3, 0.13, Hostile;
+    EnderDragon = "ender_dragon", 16.0, 8.0, 6.8, Hostile;
+    EnderPearl = "ender_pearl", 0.25, 0.25, 0.2125, Projectile;
+    EndCrystal = "end_crystal", 2.0, 2.0, 1.7, Misc;
+    Evoker = "evoker", 0.6, 1.95, 1.6575, Hostile;
+    EvokerFangs = "evoker_fangs", 0.5, 0.8, 0.68, Misc;
+    ExperienceBottle = "experience_bottle", 0.25, 0.25, 0.2125, Projectile;
+    ExperienceOrb = "experience_orb", 0.5, 0.5, 0.425, Misc;
+    EyeOfEnder = "eye_of_ender", 0.25, 0.25, 0.2125, Misc;
+    FallingBlock = "falling_block", 0.98, 0.98, 0.833, Misc;
+    Fireball = "fireball", 1.0, 1.0, 0.85, Projectile;
+    FireworkRocket = "firework_rocket", 0.25, 0.25, 0.2125, Projectile;
+    Fox = "fox", 0.6, 0.7, 0.4, Passive;
+    Frog = "frog", 0.5, 0.5, 0.425, Passive;
+    FurnaceMinecart = "furnace_minecart", 0.98, 0.7, 0.595, Vehicle;
+    Ghast = "ghast", 4.0, 4.0, 2.6, Hostile;
+    HappyGhast = "happy_ghast", 4.0, 4.0, 2.6, Passive;
+    Giant = "giant", 3.6, 12.0, 10.44, Hostile;
+    GlowItemFrame = "glow_item_frame", 0.5, 0.5, 0.0, Misc;
+    GlowSquid = "glow_squid", 0.8, 0.8, 0.4, Passive;
+    Goat = "goat", 0.9, 1.3, 1.105, Passive;
+    Guardian = "guardian", 0.85, 0.85, 0.425, Hostile;
+    Hoglin = "hoglin", 1.3964844, 1.4, 1.19, Hostile;
+    HopperMinecart = "hopper_minecart", 0.98, 0.7, 0.595, Vehicle;
+    Horse = "horse", 1.3964844, 1.6, 1.52, Passive;
+    Husk = "husk", 0.6, 1.95, 1.74, Hostile;
+    Illusioner = "illusioner", 0.6, 1.95, 1.6575, Hostile;
+    Interaction = "interaction", 0.0, 0.0, 0.0, Misc;
+    IronGolem = "iron_golem", 1.4, 2.7, 2.295, Passive;
+    Item = "item", 0.25, 0.25, 0.2125, Item;
+    ItemDisplay = "item_display", 0.0, 0.0, 0.0, Misc;
+    ItemFrame = "item_frame", 0.5, 0.5, 0.0, Misc;
+    JungleBoat = "jungle_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    JungleChestBoat = "jungle_chest_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    LeashKnot = "leash_knot", 0.375, 0.5, 0.0625, Misc;
+    LightningBolt = "lightning_bolt", 0.0, 0.0, 0.0, Misc;
+    Llama = "llama", 0.9, 1.87, 1.7765, Passive;
+    LlamaSpit = "llama_spit", 0.25, 0.25, 0.2125, Projectile;
+    MagmaCube = "magma_cube", 0.52, 0.52, 0.325, Hostile;
+    MangroveBoat = "mangrove_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    MangroveChestBoat = "mangrove_chest_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    Marker = "marker", 0.0, 0.0, 0.0, Misc;
+    Minecart = "minecart", 0.98, 0.7, 0.595, Vehicle;
+    Mooshroom = "mooshroom", 0.9, 1.4, 1.3, Passive;
+    Mule = "mule", 1.3964844, 1.6, 1.52, Passive;
+    OakBoat = "oak_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    OakChestBoat = "oak_chest_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    Ocelot = "ocelot", 0.6, 0.7, 0.595, Passive;
+    OminousItemSpawner = "ominous_item_spawner", 0.25, 0.25, 0.2125, Misc;
+    Painting = "painting", 0.5, 0.5, 0.0, Misc;
+    PaleOakBoat = "pale_oak_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    PaleOakChestBoat = "pale_oak_chest_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    Panda = "panda", 1.3, 1.25, 1.0625, Passive;
+    Parrot = "parrot", 0.5, 0.9, 0.54, Passive;
+    Phantom = "phantom", 0.9, 0.5, 0.175, Hostile;
+    Pig = "pig", 0.9, 0.9, 0.765, Passive;
+    Piglin = "piglin", 0.6, 1.95, 1.79, Hostile;
+    PiglinBrute = "piglin_brute", 0.6, 1.95, 1.79, Hostile;
+    Pillager = "pillager", 0.6, 1.95, 1.6575, Hostile;
+    PolarBear = "polar_bear", 1.4, 1.4, 1.19, Passive;
+    SplashPotion = "splash_potion", 0.25, 0.25, 0.2125, Projectile;
+    LingeringPotion = "lingering_potion", 0.25, 0.25, 0.2125, Projectile;
+    Pufferfish = "pufferfish", 0.7, 0.7, 0.455, Passive;
+    Rabbit = "rabbit", 0.4, 0.5, 0.425, Passive;
+    Ravager = "ravager", 1.95, 2.2, 1.87, Hostile;
+    Salmon = "salmon", 0.7, 0.4, 0.26, Passive;
+    Sheep = "sheep", 0.9, 1.3, 1.235, Passive;
+    Shulker = "shulker", 1.0, 1.0, 0.5, Hostile;
+    ShulkerBullet = "shulker_bullet", 0.3125, 0.3125, 0.265625, Projectile;
+    Silverfish = "silverfish", 0.4, 0.3, 0.13, Hostile;
+    Skeleton = "skeleton", 0.6, 1.99, 1.74, Hostile;
+    SkeletonHorse = "skeleton_horse", 1.3964844, 1.6, 1.52, Passive;
+    Slime = "slime", 0.52, 0.52, 0.325, Hostile;
+    SmallFireball = "small_fireball", 0.3125, 0.3125, 0.265625, Projectile;
+    Sniffer = "sniffer", 1.9, 1.75, 1.05, Passive;
+    Snowball = "snowball", 0.25, 0.25, 0.2125, Projectile;
+    SnowGolem = "snow_golem", 0.7, 1.9, 1.7, Passive;
+    SpawnerMinecart = "spawner_minecart", 0.98, 0.7, 0.595, Vehicle;
+    SpectralArrow = "spectral_arrow", 0.5, 0.5, 0.13, Projectile;
+    Spider = "spider", 1.4, 0.9, 0.65, Hostile;
+    SpruceBoat = "spruce_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    SpruceChestBoat = "spruce_chest_boat", 1.375, 0.5625, 0.5625, Vehicle;
+    Squid = "squid", 0.8, 0.8, 0.4, Passive;
+    Stray = "stray", 0.6, 1.99, 1.74, Hostile;
+    Strider = "strider", 0.9, 1.7, 1.445, Passive;
+    Tadpole = "tadpole", 0.4, 0.3, 0.19, Passive;
+    TextDisplay = "text_display", 0.0, 0.0, 0.0, Misc;
+    Tnt = "tnt", 0.98, 0.98, 0.15, Misc;
+    TntMinecart = "tnt_minecart", 0.98, 0.7, 0.595, Vehicle;
+    TraderLlama = "trader_llama", 0.9, 1.87, 1.7765, Passive;
+    Trident = "trident", 0.5, 0.5, 0.13, Projectile;
+    TropicalFish = "tropical_fish", 0.5, 0.4, 0.26, Passive;
+    Turtle = "turtle", 1.2, 0.4, 0.34, Passive;
+    Vex = "vex", 0.4, 0.8, 0.51875, Hostile;
+    Villager = "villager", 0.6, 1.95, 1.62, Passive;
+    Vindicator = "vindicator", 0.6, 1.95, 1.6575, Hostile;
+    WanderingTrader = "wandering_trader", 0.6, 1.95, 1.62, Passive;
+    Warden = "warden", 0.9, 2.9, 2.465, Hostile;
+    WindCharge = "wind_charge", 0.3125, 0.3125, 0.0, Projectile;
+    Witch = "witch", 0.6, 1.95, 1.62, Hostile;
+    Wither = "wither", 0.9, 3.5, 2.975, Hostile;
+    WitherSkeleton = "wither_skeleton", 0.7, 2.4, 2.1, Hostile;
+    WitherSkull = "wither_skull", 0.3125, 0.3125, 0.265625, Projectile;
+    Wolf = "wolf", 0.6, 0.85, 0.68, Passive;
+    Zoglin = "zoglin", 1.3964844, 1.4, 1.19, Hostile;
+    Zombie = "zombie", 0.6, 1.95, 1.74, Hostile;
+    ZombieHorse = "zombie_horse", 1.3964844, 1.6, 1.52, Passive;
+    ZombieVillager = "zombie_villager", 0.6, 1.95, 1.74, Hostile;
+    ZombifiedPiglin = "zombified_piglin", 0.6, 1.95, 1.79, Hostile;
+    Player = "player", 0.6, 1.8, 1.62, Player;
+    FishingBobber = "fishing_bobber", 0.25, 0.25, 0.2125, Projectile;
+}
+
+impl Default for EntityType {
+    /// The entity of the player is created before the server sends anything
+    fn default() -> Self {
+        Self::Player
+    }
+}
+
+impl Display for EntityType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+#[allow(dead_code)]
+impl EntityType {
+    pub fn info(self) -> &'static EntityTypeInfo {
+        &ENTITY_TYPES[self as usize]
+    }
+
+    pub fn from_id(id: i32) -> Option<Self> {
+        usize::try_from(id)
+            .ok()
+            .and_then(|id| ALL_ENTITY_TYPES.get(id))
+            .copied()
+    }
+
+    /// Accept `minecraft:zombie` and `zombie`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        ENTITY_TYPES
+            .iter()
+            .position(|t| t.name.strip_prefix("minecraft:") == Some(name))
+            .map(|id| ALL_ENTITY_TYPES[id])
+    }
+
+    /// Name of the entity type (e.g. `minecraft:zombie`)
+    pub fn name(self) -> &'static str {
+        self.info().name
+    }
+
+    pub fn category(self) -> EntityCategory {
+        self.info().category
+    }
+
+    pub fn is_hostile(self) -> bool {
+        self.category() == EntityCategory::Hostile
+    }
+
+    /// Mobs and players, which have health and equipment
+    pub fn is_living(self) -> bool {
+        matches!(
+            self.category(),
+            EntityCategory::Hostile | EntityCategory::Passive | EntityCategory::Player
+        ) || self == Self::ArmorStand
+    }
+}
@@ -1,4 +1,6 @@
 use std::{
+    env,
+    path::Path,
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
@@ -9,9 +11,22 @@ use std::{
 
 use parking_lot::RwLock;
 
-use crate::{
-    datatypes::BlockPos, game::{Game, Vec3d, Vec3i}, packets::{send_packet_from_thread, PlayerPosFlags, ReceiveError, SetPlayerPosition}
-};
+use crate::{game::Game, packets::ReceiveError};
+
+mod behavior;
+mod movement;
+mod script;
+
+pub use behavior::*;
+pub use movement::*;
+pub use script::*;
+
+/// Distance at which the player turns to face the other players
+const LOOK_AT_PLAYERS_RANGE: f64 = 32.;
+/// Script loaded at startup if it exists, `BOT_SCRIPT` overrides it
+const DEFAULT_SCRIPT: &str = "bot.rhai";
+/// Scripts run instead of the built-in behaviors when they want to
+const SCRIPT_PRIORITY: i32 = 10;
 
 static SHOULD_RUN: AtomicBool = AtomicBool::new(true);
 
@@ -38,6 +53,16 @@ fn gameloop(game: Arc<RwLock<Game>>) -> Result<(), ReceiveError> {
     const TPS: u64 = 20;
     const TICK_TIME: Duration = Duration::from_nanos(1_000_000_000 / TPS);
 
+    let mut behaviors =
+        vec![Box::new(LookAtPlayers::new(LOOK_AT_PLAYERS_RANGE)) as Box<dyn Behavior>];
+    let script = env::var("BOT_SCRIPT").unwrap_or(DEFAULT_SCRIPT.to_string());
+    if Path::new(&script).exists() {
+        behaviors.push(Box::new(ScriptBehavior::new(script, SCRIPT_PRIORITY)));
+    }
+    for behavior in behaviors {
+        game.write().behaviors.add(behavior);
+    }
+
     while SHOULD_RUN.load(Ordering::Relaxed) {
         let starting_time = Instant::now();
 
@@ -53,30 +78,12 @@ fn gameloop(game: Arc<RwLock<Game>>) -> Result<(), ReceiveError> {
 }
 
 fn game_logic(game: &RwLock<Game>) -> Result<(), ReceiveError> {
-    let mut player_entity = game.read().player.entity.write_arc();
-
-    let (sin, cos) = (player_entity.rotation.yaw as f64).to_radians().sin_cos();
-    let look_at = Vec3d {
-        x: -sin,
-        y: 0.,
-        z: cos,
-    };
-
-    let pos = player_entity.position;
-    let new_pos = pos + look_at * 0.1;
-    player_entity.position = new_pos;
-    drop(player_entity);
-
-    send_packet_from_thread(SetPlayerPosition {
-        pos: new_pos,
-        flags: PlayerPosFlags::empty(),
-    })?;
-
-    let block = game.read().world.block_at(BlockPos(Vec3i {
-        y: 70,
-        ..Default::default()
-    }));
-    dbg!(block);
+    let mut game = game.write();
+    game.tick_entities();
+    game.tick_behaviors()?;
+    game.tick_digging()?;
+    game.tick_movement();
+    game.tick_physics()?;
 
     Ok(())
 }
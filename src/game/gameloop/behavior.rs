@@ -0,0 +1,196 @@
+use std::{collections::HashSet, fmt::Debug};
+
+use parking_lot::Mutex;
+
+use crate::game::{EntityCategory, EntityId, Game, GameError, Rotation};
+
+/// Something that happened since the last tick, given to every behavior
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    EntityAdded(EntityId),
+    /// The server sent a new position for the entity
+    EntityMoved(EntityId),
+    EntityRemoved(EntityId),
+    /// After a death or a change of dimension
+    Respawned,
+}
+
+/// Part of the bot logic, run by the `BehaviorScheduler`.
+///
+/// Each tick, all the behaviors get the events, then only the one with the highest priority among
+/// the ones that want to run has its `on_tick` called.
+pub trait Behavior: Debug + Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Behaviors with a higher priority preempt the others
+    fn priority(&self) -> i32;
+
+    /// Whether the behavior has something to do this tick
+    fn should_run(&self, game: &Game) -> bool;
+
+    fn on_tick(&mut self, game: &mut Game) -> Result<(), GameError>;
+
+    /// Called at the start of each tick with the events since the last one, which may be none
+    fn on_events(&mut self, _game: &Game, _events: &[GameEvent]) {}
+
+    /// Called when the behavior gets to run after another one, or none
+    fn on_start(&mut self, _game: &mut Game) {}
+
+    /// Called when the behavior is preempted or doesn't want to run anymore
+    fn on_stop(&mut self, _game: &mut Game) {}
+}
+
+#[derive(Debug, Default)]
+pub struct BehaviorScheduler {
+    behaviors: Vec<Box<dyn Behavior>>,
+    /// Index of the behavior which ran last tick
+    active: Option<usize>,
+    /// Filled by the packet handlers, which can only read the game
+    events: Mutex<Vec<GameEvent>>,
+}
+
+impl BehaviorScheduler {
+    #[allow(dead_code)]
+    pub fn add(&mut self, behavior: Box<dyn Behavior>) {
+        let active = self.active.map(|i| self.behaviors[i].name().to_string());
+        self.behaviors.push(behavior);
+        // Kept sorted so that the first behavior that wants to run is the one with the priority
+        self.behaviors
+            .sort_by_key(|b| std::cmp::Reverse(b.priority()));
+        self.active = active.and_then(|name| self.behaviors.iter().position(|b| b.name() == name));
+    }
+
+    #[allow(dead_code)]
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn Behavior>> {
+        let index = self.behaviors.iter().position(|b| b.name() == name)?;
+        match self.active {
+            Some(active) if active == index => self.active = None,
+            Some(active) if active > index => self.active = Some(active - 1),
+            _ => (),
+        }
+        Some(self.behaviors.remove(index))
+    }
+
+    pub fn push_event(&self, event: GameEvent) {
+        self.events.lock().push(event);
+    }
+}
+
+impl Game {
+    /// Give the events to the behaviors and run the one with the highest priority
+    pub fn tick_behaviors(&mut self) -> Result<(), GameError> {
+        // Taken out of the game so that the behaviors can change it
+        let mut scheduler = std::mem::take(&mut self.behaviors);
+        let result = scheduler.tick(self);
+        // Events pushed by the behaviors are kept for the next tick
+        scheduler
+            .events
+            .lock()
+            .append(&mut self.behaviors.events.lock());
+        self.behaviors = scheduler;
+        result
+    }
+}
+
+/// Only keep the last move of each entity, busy areas send several per tick
+fn coalesce_moves(events: Vec<GameEvent>) -> Vec<GameEvent> {
+    let mut moved = HashSet::new();
+    let mut events: Vec<GameEvent> = events
+        .into_iter()
+        .rev()
+        .filter(|event| match event {
+            GameEvent::EntityMoved(id) => moved.insert(*id),
+            _ => true,
+        })
+        .collect();
+    events.reverse();
+    events
+}
+
+impl BehaviorScheduler {
+    fn tick(&mut self, game: &mut Game) -> Result<(), GameError> {
+        let events = coalesce_moves(std::mem::take(&mut *self.events.lock()));
+        for behavior in &mut self.behaviors {
+            behavior.on_events(game, &events);
+        }
+
+        let next = self.behaviors.iter().position(|b| b.should_run(game));
+        if next != self.active {
+            if let Some(previous) = self.active {
+                self.behaviors[previous].on_stop(game);
+            }
+            if let Some(next) = next {
+                self.behaviors[next].on_start(game);
+            }
+            self.active = next;
+        }
+        match next {
+            Some(next) => self.behaviors[next].on_tick(game),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Turn to face the last player that moved, while it's in range
+#[derive(Debug)]
+pub struct LookAtPlayers {
+    pub range: f64,
+    target: Option<EntityId>,
+}
+
+impl LookAtPlayers {
+    pub fn new(range: f64) -> Self {
+        Self {
+            range,
+            target: None,
+        }
+    }
+}
+
+impl Behavior for LookAtPlayers {
+    fn name(&self) -> &str {
+        "look_at_players"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn should_run(&self, game: &Game) -> bool {
+        // The movement controller turns the player towards where it goes
+        self.target.is_some() && !game.movement.is_moving()
+    }
+
+    fn on_events(&mut self, game: &Game, events: &[GameEvent]) {
+        for event in events {
+            match *event {
+                GameEvent::EntityMoved(id) if id != game.player.entity_id => {
+                    let is_player = game
+                        .entities
+                        .get(id)
+                        .is_some_and(|e| e.entity_type.category() == EntityCategory::Player);
+                    if is_player {
+                        self.target = Some(id);
+                    }
+                }
+                GameEvent::EntityRemoved(id) if self.target == Some(id) => self.target = None,
+                _ => (),
+            }
+        }
+    }
+
+    fn on_tick(&mut self, game: &mut Game) -> Result<(), GameError> {
+        let Some(target) = self.target.and_then(|id| game.entities.get(id)) else {
+            self.target = None;
+            return Ok(());
+        };
+        let mut player = game.player.entity.write();
+        if (target.position - player.position).length() > self.range {
+            self.target = None;
+            return Ok(());
+        }
+        player.rotation = Rotation::looking_at(player.eye_position(), target.eye_position());
+        Ok(())
+    }
+}
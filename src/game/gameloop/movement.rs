@@ -0,0 +1,284 @@
+use std::sync::mpsc::{Receiver, Sender, TryRecvError, channel};
+
+use parking_lot::Mutex;
+
+use crate::{
+    datatypes::BlockPos,
+    game::{
+        EntityId, Game, GameError, MovementInput, Path, PathMove, PathOptions, Rotation, Vec3d,
+        Vec3i,
+    },
+};
+
+/// Ticks without moving before the path is searched again
+const STUCK_TICKS: u32 = 30;
+/// Searches of a path before giving up
+const MAX_REPLANS: u32 = 5;
+/// Distance to the center of a node at which it's considered reached
+const NODE_REACHED: f64 = 0.35;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MovementGoal {
+    GoTo(BlockPos),
+    /// Get within `range` blocks of a block
+    Near {
+        pos: BlockPos,
+        range: f32,
+    },
+    /// Get within `range` blocks of an entity
+    NearEntity {
+        entity: EntityId,
+        range: f32,
+    },
+    /// Keep within `distance` blocks of an entity, until stopped
+    Follow {
+        entity: EntityId,
+        distance: f32,
+    },
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum MovementResult {
+    Reached,
+    /// Replaced by another goal or stopped
+    Cancelled,
+    Failed(GameError),
+}
+
+#[derive(Debug)]
+struct ActiveGoal {
+    goal: MovementGoal,
+    result: Sender<MovementResult>,
+    path: Option<Path>,
+    /// Index of the next node to reach in `path`
+    next_node: usize,
+    /// Path being searched, in a mutex as the receiver can't be shared between threads
+    searching: Option<Mutex<Receiver<Result<Path, GameError>>>>,
+    /// Target of the current path, entities may have moved since
+    planned_target: BlockPos,
+    last_position: Vec3d,
+    stuck_ticks: u32,
+    replans: u32,
+}
+
+/// Steers the player towards a goal, following a path from the pathfinder
+#[derive(Debug, Default)]
+pub struct MovementController {
+    goal: Option<ActiveGoal>,
+}
+
+impl MovementController {
+    #[allow(dead_code)]
+    pub fn is_moving(&self) -> bool {
+        self.goal.is_some()
+    }
+
+    fn finish(&mut self, result: MovementResult) {
+        if let Some(goal) = self.goal.take() {
+            // The requester may have given up waiting
+            let _ = goal.result.send(result);
+        }
+    }
+}
+
+fn node_center(pos: BlockPos) -> Vec3d {
+    Vec3d {
+        x: pos.0.x as f64 + 0.5,
+        y: pos.0.y as f64,
+        z: pos.0.z as f64 + 0.5,
+    }
+}
+
+fn block_at_position(pos: Vec3d) -> BlockPos {
+    BlockPos(Vec3i {
+        x: pos.x.floor() as i32,
+        y: pos.y.floor() as i32,
+        z: pos.z.floor() as i32,
+    })
+}
+
+impl Game {
+    /// Start moving towards `goal`, replacing the current one. The receiver gets the outcome.
+    #[allow(dead_code)]
+    pub fn start_moving(&mut self, goal: MovementGoal) -> Receiver<MovementResult> {
+        self.movement.finish(MovementResult::Cancelled);
+        let (sender, receiver) = channel();
+        self.movement.goal = Some(ActiveGoal {
+            goal,
+            result: sender,
+            path: None,
+            next_node: 0,
+            searching: None,
+            planned_target: BlockPos::default(),
+            last_position: self.player.entity.read().position,
+            stuck_ticks: 0,
+            replans: 0,
+        });
+        receiver
+    }
+
+    #[allow(dead_code)]
+    pub fn stop_moving(&mut self) {
+        self.movement.finish(MovementResult::Cancelled);
+        self.player.physics.input = MovementInput::default();
+    }
+
+    /// Block to reach and the allowed distance to it
+    fn goal_target(&self, goal: MovementGoal) -> Result<(BlockPos, f32), GameError> {
+        let entity_pos = |id| {
+            self.entities
+                .get(id)
+                .map(|e| block_at_position(e.position))
+                .ok_or(GameError::UnkonwnEntity(id))
+        };
+        Ok(match goal {
+            MovementGoal::GoTo(pos) => (pos, 0.),
+            MovementGoal::Near { pos, range } => (pos, range),
+            MovementGoal::NearEntity { entity, range } => (entity_pos(entity)?, range),
+            MovementGoal::Follow { entity, distance } => (entity_pos(entity)?, distance),
+        })
+    }
+
+    fn end_movement(&mut self, result: MovementResult) {
+        self.movement.finish(result);
+        self.player.physics.input = MovementInput::default();
+    }
+
+    /// Set the movement input of the player for this tick, called before the physics
+    pub fn tick_movement(&mut self) {
+        let Some(active) = &self.movement.goal else {
+            return;
+        };
+        let goal = active.goal;
+        let (target, range) = match self.goal_target(goal) {
+            Ok(target) => target,
+            Err(e) => return self.end_movement(MovementResult::Failed(e)),
+        };
+
+        let player_pos = self.player_block_pos();
+        if player_pos.distance(target) <= range {
+            if let MovementGoal::Follow { .. } = goal {
+                // Wait for the entity to move away
+                let active = self.movement.goal.as_mut().unwrap();
+                active.path = None;
+                self.player.physics.input = MovementInput::default();
+                return;
+            }
+            return self.end_movement(MovementResult::Reached);
+        }
+
+        let position = self.player.entity.read().position;
+        let in_water = self
+            .world
+            .block_at(player_pos)
+            .is_some_and(|s| s.is_water());
+        let horizontal_collision = self.player.physics.horizontal_collision;
+
+        let active = self.movement.goal.as_mut().unwrap();
+
+        if let Some(searching) = &active.searching {
+            let received = searching.lock().try_recv();
+            match received {
+                Ok(Ok(path)) => {
+                    active.path = Some(path);
+                    active.next_node = 1;
+                    active.searching = None;
+                }
+                Ok(Err(e)) => return self.end_movement(MovementResult::Failed(e)),
+                Err(TryRecvError::Empty) => {
+                    self.player.physics.input = MovementInput::default();
+                    return;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    return self.end_movement(MovementResult::Failed(GameError::NoPath(target)));
+                }
+            }
+        }
+
+        // Moving entities are followed with a new path once they got away from the old target
+        let target_moved = active.planned_target.distance(target) > range.max(1.) + 2.;
+        let path_done = active
+            .path
+            .as_ref()
+            .is_some_and(|p| active.next_node >= p.nodes.len());
+        if active.path.is_none() || target_moved || path_done {
+            if path_done && active.path.as_ref().is_some_and(|p| p.complete) && !target_moved {
+                // At the end of a complete path without being in range, the goal is unreachable
+                return self.end_movement(MovementResult::Failed(GameError::NoPath(target)));
+            }
+            let options = PathOptions {
+                range,
+                partial: true,
+                ..Default::default()
+            };
+            let searching = self.find_path(target, options);
+            let active = self.movement.goal.as_mut().unwrap();
+            active.path = None;
+            active.planned_target = target;
+            active.searching = Some(Mutex::new(searching));
+            self.player.physics.input = MovementInput::default();
+            return;
+        }
+
+        let path = active.path.as_ref().unwrap();
+        let mut node = path.nodes[active.next_node];
+        let delta = node_center(node.pos) - position;
+        let reached =
+            delta.x.abs() < NODE_REACHED && delta.z.abs() < NODE_REACHED && delta.y.abs() < 1.;
+        if reached {
+            active.next_node += 1;
+            active.stuck_ticks = 0;
+            // Only consecutive stuck episodes make the goal fail
+            active.replans = 0;
+            match path.nodes.get(active.next_node) {
+                Some(next) => node = *next,
+                None => {
+                    self.player.physics.input = MovementInput::default();
+                    return;
+                }
+            }
+        }
+
+        // Stuck against something the path didn't expect, like a block placed since
+        let moved = position - active.last_position;
+        active.last_position = position;
+        if moved.x.abs() + moved.z.abs() < 0.01 {
+            active.stuck_ticks += 1;
+        } else {
+            active.stuck_ticks = 0;
+        }
+        if active.stuck_ticks > STUCK_TICKS {
+            active.replans += 1;
+            if active.replans > MAX_REPLANS {
+                return self.end_movement(MovementResult::Failed(GameError::Stuck));
+            }
+            active.stuck_ticks = 0;
+            active.path = None;
+            return;
+        }
+
+        // Look at the next node at eye level, which keeps the head level on flat ground and tilts
+        // it up or down along slopes and drops
+        let center = node_center(node.pos);
+        let delta = center - position;
+        let mut player = self.player.entity.write();
+        let eye_position = player.eye_position();
+        let target = Vec3d {
+            y: center.y + eye_position.y - position.y,
+            ..center
+        };
+        player.rotation = Rotation::looking_at(eye_position, target);
+        drop(player);
+
+        let climbing = node.movement == PathMove::Jump
+            || (node.movement == PathMove::Swim && delta.y > 0.)
+            || (in_water && delta.y >= 0.);
+        self.player.physics.input = MovementInput {
+            forward: 1.,
+            jump: climbing || horizontal_collision,
+            ..Default::default()
+        };
+    }
+}
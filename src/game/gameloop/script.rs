@@ -0,0 +1,393 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use log::{error, info};
+use parking_lot::Mutex;
+use rhai::{AST, Array, CallFnOptions, Dynamic, Engine, FLOAT, INT, Map, Scope};
+
+use crate::{
+    datatypes::BlockPos,
+    game::{
+        Behavior, Game, GameError, GameEvent, MovementGoal, Rotation, Vec3d, Vec3i, world::World,
+    },
+};
+
+/// Operations a callback can run before being stopped, so that a loop can't freeze the game
+const MAX_OPERATIONS: u64 = 100_000;
+/// Sizes of the values a script can build, so that it can't use up the memory
+const MAX_STRING_SIZE: usize = 64 * 1024;
+const MAX_ARRAY_SIZE: usize = 100_000;
+const MAX_MAP_SIZE: usize = 10_000;
+/// Time between two checks of the modification time of the script
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Action requested by the script, run once the callback returns
+#[derive(Debug)]
+enum ScriptAction {
+    MoveTo(BlockPos),
+    Stop,
+    LookAt(Vec3d),
+    Dig(BlockPos),
+    Say(String),
+}
+
+#[derive(Debug, Clone)]
+struct EntitySnapshot {
+    id: i32,
+    entity_type: &'static str,
+    category: String,
+    position: Vec3d,
+}
+
+/// What the script can see of the game, updated before each callback
+#[derive(Debug, Default)]
+struct ScriptContext {
+    world: World,
+    position: Vec3d,
+    rotation: Rotation,
+    is_moving: bool,
+    entities: Vec<EntitySnapshot>,
+    actions: Vec<ScriptAction>,
+}
+
+fn block_pos(x: INT, y: INT, z: INT) -> BlockPos {
+    BlockPos(Vec3i {
+        x: x as i32,
+        y: y as i32,
+        z: z as i32,
+    })
+}
+
+fn vec_map(pos: Vec3d) -> Map {
+    let mut map = Map::new();
+    map.insert("x".into(), pos.x.into());
+    map.insert("y".into(), pos.y.into());
+    map.insert("z".into(), pos.z.into());
+    map
+}
+
+fn event_map(event: GameEvent) -> Map {
+    let (kind, entity) = match event {
+        GameEvent::EntityAdded(id) => ("entity_added", Some(id)),
+        GameEvent::EntityMoved(id) => ("entity_moved", Some(id)),
+        GameEvent::EntityRemoved(id) => ("entity_removed", Some(id)),
+        GameEvent::Respawned => ("respawned", None),
+    };
+    let mut map = Map::new();
+    map.insert("kind".into(), kind.into());
+    if let Some(entity) = entity {
+        map.insert("entity".into(), (i32::from(entity) as INT).into());
+    }
+    map
+}
+
+/// Engine exposing the game to the scripts:
+/// - `position()`, `rotation()`, `is_moving()` of the player
+/// - `block_at(x, y, z)`, the name of the block or `()` if its chunk isn't loaded
+/// - `entities()`, maps with the `id`, `type`, `category`, `x`, `y` and `z` of each entity
+/// - `move_to(x, y, z)`, `stop()`, `look_at(x, y, z)`, `dig(x, y, z)` and `say(message)`
+fn create_engine(context: &Arc<Mutex<ScriptContext>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_string_size(MAX_STRING_SIZE);
+    engine.set_max_array_size(MAX_ARRAY_SIZE);
+    engine.set_max_map_size(MAX_MAP_SIZE);
+    engine.on_print(|message| info!("[script] {message}"));
+    engine.on_debug(|message, _, pos| info!("[script] {pos:?} {message}"));
+
+    let ctx = Arc::clone(context);
+    engine.register_fn("position", move || vec_map(ctx.lock().position));
+    let ctx = Arc::clone(context);
+    engine.register_fn("rotation", move || {
+        let rotation = ctx.lock().rotation;
+        let mut map = Map::new();
+        map.insert("yaw".into(), (rotation.yaw as FLOAT).into());
+        map.insert("pitch".into(), (rotation.pitch as FLOAT).into());
+        map
+    });
+    let ctx = Arc::clone(context);
+    engine.register_fn("is_moving", move || ctx.lock().is_moving);
+    let ctx = Arc::clone(context);
+    engine.register_fn("block_at", move |x: INT, y: INT, z: INT| {
+        ctx.lock()
+            .world
+            .block_at(block_pos(x, y, z))
+            .map_or(Dynamic::UNIT, |state| state.name().into())
+    });
+    let ctx = Arc::clone(context);
+    engine.register_fn("entities", move || {
+        ctx.lock()
+            .entities
+            .iter()
+            .map(|e| {
+                let mut map = vec_map(e.position);
+                map.insert("id".into(), (e.id as INT).into());
+                map.insert("type".into(), e.entity_type.into());
+                map.insert("category".into(), e.category.clone().into());
+                map.into()
+            })
+            .collect::<Array>()
+    });
+
+    let ctx = Arc::clone(context);
+    engine.register_fn("move_to", move |x: INT, y: INT, z: INT| {
+        ctx.lock()
+            .actions
+            .push(ScriptAction::MoveTo(block_pos(x, y, z)));
+    });
+    let ctx = Arc::clone(context);
+    engine.register_fn("stop", move || ctx.lock().actions.push(ScriptAction::Stop));
+    let ctx = Arc::clone(context);
+    engine.register_fn("look_at", move |x: FLOAT, y: FLOAT, z: FLOAT| {
+        ctx.lock()
+            .actions
+            .push(ScriptAction::LookAt(Vec3d { x, y, z }));
+    });
+    let ctx = Arc::clone(context);
+    engine.register_fn("dig", move |x: INT, y: INT, z: INT| {
+        ctx.lock()
+            .actions
+            .push(ScriptAction::Dig(block_pos(x, y, z)));
+    });
+    let ctx = Arc::clone(context);
+    engine.register_fn("say", move |message: &str| {
+        ctx.lock()
+            .actions
+            .push(ScriptAction::Say(message.to_string()));
+    });
+    engine
+}
+
+#[derive(Debug)]
+struct LoadedScript {
+    ast: AST,
+    scope: Scope<'static>,
+    /// Bound to `this` in the callbacks, kept between them
+    state: Dynamic,
+}
+
+impl LoadedScript {
+    fn has_fn(&self, name: &str) -> bool {
+        self.ast.iter_functions().any(|f| f.name == name)
+    }
+}
+
+/// Behavior running the callbacks of a Rhai script, reloaded when the file changes.
+///
+/// The script can define `on_tick()`, `on_events(events)` and `should_run()`, which defaults to
+/// whether `on_tick` is defined. `on_events` gets the array of the events since the last tick, if
+/// there are any. `this` is a map kept between the calls.
+///
+/// What the script sees of the game is taken once per tick, before `on_events`.
+#[derive(Debug)]
+pub struct ScriptBehavior {
+    path: PathBuf,
+    priority: i32,
+    engine: Engine,
+    context: Arc<Mutex<ScriptContext>>,
+    /// In a mutex as `should_run` only gets a shared reference
+    script: Mutex<Option<LoadedScript>>,
+    /// Modification time of the file when it was last loaded, even if the loading failed
+    modified: Mutex<Option<SystemTime>>,
+    last_check: Mutex<Instant>,
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl ScriptBehavior {
+    pub fn new(path: impl Into<PathBuf>, priority: i32) -> Self {
+        let context = Arc::default();
+        let behavior = Self {
+            path: path.into(),
+            priority,
+            engine: create_engine(&context),
+            context,
+            script: Mutex::new(None),
+            modified: Mutex::new(None),
+            last_check: Mutex::new(Instant::now()),
+        };
+        *behavior.script.lock() = behavior.load();
+        behavior
+    }
+
+    /// Compile the script and run its top level statements, errors are logged
+    fn load(&self) -> Option<LoadedScript> {
+        *self.modified.lock() = modified_time(&self.path);
+        let ast = match self.engine.compile_file(self.path.clone()) {
+            Ok(ast) => ast,
+            Err(e) => {
+                error!("Can't load the script {}: {e}", self.path.display());
+                return None;
+            }
+        };
+        let mut scope = Scope::new();
+        if let Err(e) = self.engine.run_ast_with_scope(&mut scope, &ast) {
+            error!("Error in the script {}: {e}", self.path.display());
+            return None;
+        }
+        info!("Script {} loaded", self.path.display());
+        Some(LoadedScript {
+            ast,
+            scope,
+            state: Map::new().into(),
+        })
+    }
+
+    /// Load the script again if its file changed since it was loaded
+    fn reload_if_changed(&self) {
+        let mut last_check = self.last_check.lock();
+        if last_check.elapsed() < RELOAD_CHECK_INTERVAL {
+            return;
+        }
+        *last_check = Instant::now();
+
+        let modified = modified_time(&self.path);
+        if modified.is_some() && modified != *self.modified.lock() {
+            // A broken script is unloaded, so that the bot doesn't run an outdated one
+            let script = self.load();
+            *self.script.lock() = script;
+        }
+    }
+
+    fn update_context(&self, game: &Game) {
+        let player = game.player.entity.read();
+        let entities = game
+            .entities
+            .all()
+            .into_iter()
+            .filter(|(id, _)| *id != game.player.entity_id)
+            .map(|(id, entity)| {
+                let entity = entity.read();
+                EntitySnapshot {
+                    id: id.into(),
+                    entity_type: entity.entity_type.name(),
+                    category: format!("{:?}", entity.entity_type.category()).to_lowercase(),
+                    position: entity.position,
+                }
+            })
+            .collect();
+
+        let mut context = self.context.lock();
+        context.world = game.world.clone();
+        context.position = player.position;
+        context.rotation = player.rotation;
+        context.is_moving = game.movement.is_moving();
+        context.entities = entities;
+    }
+
+    fn defines(&self, name: &str) -> bool {
+        self.script.lock().as_ref().is_some_and(|s| s.has_fn(name))
+    }
+
+    /// Call a function of the script if it's defined, errors are logged
+    fn call<T: Clone + Send + Sync + 'static>(
+        &self,
+        name: &str,
+        args: impl rhai::FuncArgs,
+    ) -> Option<T> {
+        let mut script = self.script.lock();
+        let script = script.as_mut().filter(|s| s.has_fn(name))?;
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut script.state);
+        match self
+            .engine
+            .call_fn_with_options(options, &mut script.scope, &script.ast, name, args)
+        {
+            Ok(value) => Some(value),
+            Err(e) => {
+                error!(
+                    "Error in {} of the script {}: {e}",
+                    name,
+                    self.path.display()
+                );
+                None
+            }
+        }
+    }
+
+    /// Run the actions requested by the script
+    fn apply_actions(&self, game: &mut Game) {
+        let actions = std::mem::take(&mut self.context.lock().actions);
+        for action in actions {
+            let result = match action {
+                ScriptAction::MoveTo(pos) => {
+                    // The script checks `is_moving` instead of waiting for the result
+                    game.start_moving(MovementGoal::GoTo(pos));
+                    Ok(())
+                }
+                ScriptAction::Stop => {
+                    game.stop_moving();
+                    Ok(())
+                }
+                ScriptAction::LookAt(target) => {
+                    let mut player = game.player.entity.write();
+                    player.rotation = Rotation::looking_at(player.eye_position(), target);
+                    Ok(())
+                }
+                ScriptAction::Dig(pos) => game.dig(pos).map(|_| ()),
+                ScriptAction::Say(message) => game.chat.say(&message),
+            };
+            if let Err(e) = result {
+                error!("Action of the script {} failed: {e}", self.path.display());
+            }
+        }
+    }
+}
+
+impl Behavior for ScriptBehavior {
+    fn name(&self) -> &str {
+        "script"
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn should_run(&self, _game: &Game) -> bool {
+        self.reload_if_changed();
+        let res = if self.defines("should_run") {
+            let pending = self.context.lock().actions.len();
+            let res = self.call("should_run", ()).unwrap_or(false);
+            // Only the other callbacks can act
+            self.context.lock().actions.truncate(pending);
+            res
+        } else {
+            self.defines("on_tick")
+        };
+        if !res {
+            // The actions of `on_events` would be stale by the time the script runs again
+            self.context.lock().actions.clear();
+        }
+        res
+    }
+
+    fn on_tick(&mut self, game: &mut Game) -> Result<(), GameError> {
+        self.call::<Dynamic>("on_tick", ());
+        self.apply_actions(game);
+        Ok(())
+    }
+
+    fn on_stop(&mut self, _game: &mut Game) {
+        self.context.lock().actions.clear();
+    }
+
+    fn on_events(&mut self, game: &Game, events: &[GameEvent]) {
+        // Called at the start of every tick, before the other callbacks. Actions queued last tick
+        // weren't run, as a behavior with a higher priority ran instead, and are stale now.
+        self.context.lock().actions.clear();
+        self.update_context(game);
+        if events.is_empty() || !self.defines("on_events") {
+            return;
+        }
+        let events: Array = events.iter().map(|e| event_map(*e).into()).collect();
+        // The actions need to change the game, they wait for the next `on_tick`
+        self.call::<Dynamic>("on_events", (events,));
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use crate::{
+    datatypes::{BlockPos, VarInt},
+    game::{
+        BlockFace, BlockState, ChunkSectionPos, Game, Hand, LocalPos, Vec3, Vec3i, world::World,
+    },
+    packets::{ReceiveError, SwingArm, UseItemOn, send_packet_from_thread},
+};
+
+#[derive(Debug, Clone, Copy)]
+struct PendingBlock {
+    /// Sequence of the last interaction predicting this block
+    sequence: i32,
+    /// Last block sent by the server, applied once the interaction is acknowledged
+    server_state: BlockState,
+}
+
+/// Blocks changed locally before the server confirms the change.
+///
+/// Like the vanilla client, the updates of the server for these blocks are held back until the
+/// interaction is acknowledged: a refused interaction is rolled back with the block the server
+/// sent, an accepted one keeps the same block.
+#[derive(Debug, Default)]
+pub struct BlockPredictions(HashMap<BlockPos, PendingBlock>);
+
+impl BlockPredictions {
+    pub fn predict(&mut self, world: &World, pos: BlockPos, state: BlockState, sequence: i32) {
+        let Some(current) = world.block_at(pos) else {
+            return;
+        };
+        self.0
+            .entry(pos)
+            .and_modify(|p| p.sequence = sequence)
+            .or_insert(PendingBlock {
+                sequence,
+                server_state: current,
+            });
+        world.set_block(pos, state);
+    }
+
+    /// Block sent by the server, false if it's held back by a prediction
+    pub fn server_update(&mut self, pos: BlockPos, state: BlockState) -> bool {
+        match self.0.get_mut(&pos) {
+            Some(pending) => {
+                pending.server_state = state;
+                false
+            }
+            None => true,
+        }
+    }
+
+    pub fn acknowledge(&mut self, world: &World, sequence: i32) {
+        self.0.retain(|pos, pending| {
+            if pending.sequence > sequence {
+                return true;
+            }
+            world.set_block(*pos, pending.server_state);
+            false
+        });
+    }
+
+    #[allow(dead_code)]
+    pub fn is_pending(&self, pos: BlockPos) -> bool {
+        self.0.contains_key(&pos)
+    }
+}
+
+impl BlockFace {
+    /// Cursor position at the center of the face, relative to the block
+    pub fn center(self) -> Vec3<f32> {
+        let normal = self.normal();
+        Vec3 {
+            x: 0.5 + normal.x as f32 * 0.5,
+            y: 0.5 + normal.y as f32 * 0.5,
+            z: 0.5 + normal.z as f32 * 0.5,
+        }
+    }
+}
+
+impl Game {
+    pub fn block_update(&mut self, pos: BlockPos, state: BlockState) {
+        if self.predictions.server_update(pos, state) {
+            self.world.set_block(pos, state);
+        }
+    }
+
+    /// Blocks of a section changed by the server, applied at once
+    pub fn section_blocks_update(
+        &mut self,
+        section: ChunkSectionPos,
+        mut blocks: Vec<(LocalPos, BlockState)>,
+    ) {
+        blocks.retain(|(local, state)| {
+            let pos = BlockPos(Vec3i {
+                x: section.x * 16 + local.x as i32,
+                y: section.y * 16 + local.y as i32,
+                z: section.z * 16 + local.z as i32,
+            });
+            self.predictions.server_update(pos, *state)
+        });
+        self.world.set_section_blocks(section, &blocks);
+    }
+
+    pub fn block_changed_ack(&mut self, sequence: i32) {
+        self.predictions.acknowledge(&self.world, sequence);
+        self.dig_acknowledged(sequence);
+    }
+
+    /// Right click on a block, returns the sequence of the interaction
+    pub fn use_item_on(
+        &mut self,
+        pos: BlockPos,
+        face: BlockFace,
+        cursor: Vec3<f32>,
+        hand: Hand,
+    ) -> Result<i32, ReceiveError> {
+        let sequence = self.player.next_sequence();
+        send_packet_from_thread(UseItemOn {
+            hand,
+            location: pos,
+            face,
+            cursor_x: cursor.x,
+            cursor_y: cursor.y,
+            cursor_z: cursor.z,
+            inside_block: false,
+            world_border_hit: false,
+            sequence: VarInt(sequence),
+        })?;
+        send_packet_from_thread(SwingArm { hand })?;
+        Ok(sequence)
+    }
+
+    /// Place the block held in `hand` against the `face` of the block at `against`, `cursor`
+    /// being the clicked point relative to that block.
+    ///
+    /// Returns the position the block is expected at, where the world is updated at once.
+    #[allow(dead_code)]
+    pub fn place_block(
+        &mut self,
+        against: BlockPos,
+        face: BlockFace,
+        cursor: Vec3<f32>,
+        hand: Hand,
+    ) -> Result<BlockPos, ReceiveError> {
+        let clicked = self.world.block_at(against).unwrap_or_default();
+        let pos = if self.tags.block_has("replaceable", clicked) {
+            against
+        } else {
+            BlockPos(against.0 + face.normal())
+        };
+
+        let held = match hand {
+            Hand::Main => self.inventory.held_item(),
+            Hand::Off => self.inventory.offhand(),
+        };
+        // Block items share the name of their block, the placed state is predicted as the
+        // default one
+        let placed = held
+            .item()
+            .and_then(|item| BlockState::from_name(item.name(), &[]));
+
+        let sequence = self.use_item_on(against, face, cursor, hand)?;
+        if let Some(placed) = placed {
+            let can_place = self
+                .world
+                .block_at(pos)
+                .is_some_and(|s| s.is_air() || self.tags.block_has("replaceable", s));
+            if can_place {
+                self.predictions.predict(&self.world, pos, placed, sequence);
+            }
+        }
+        Ok(pos)
+    }
+
+    /// Right click on a block (door, lever, button, chest...) with the main hand
+    #[allow(dead_code)]
+    pub fn interact_block(&mut self, pos: BlockPos, face: BlockFace) -> Result<(), ReceiveError> {
+        let state = self.world.block_at(pos).unwrap_or_default();
+        let sequence = self.use_item_on(pos, face, face.center(), Hand::Main)?;
+
+        // Doors, trapdoors and fence gates open, levers switch. Iron doors need redstone.
+        let toggled = if state.name().starts_with("minecraft:iron_") {
+            None
+        } else if state.property("open").is_some() {
+            Some("open")
+        } else if state.name() == "minecraft:lever" {
+            Some("powered")
+        } else {
+            None
+        };
+        let predicted = toggled.and_then(|property| {
+            let properties: Vec<_> = state
+                .properties()
+                .map(|(p, v)| match (p == property, v) {
+                    (true, "true") => (p, "false"),
+                    (true, _) => (p, "true"),
+                    (false, _) => (p, v),
+                })
+                .collect();
+            BlockState::from_name(state.name(), &properties)
+        });
+        if let Some(predicted) = predicted {
+            self.predictions
+                .predict(&self.world, pos, predicted, sequence);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,548 @@
+use std::ops::Range;
+
+use log::warn;
+
+use crate::{
+    datatypes::VarInt,
+    game::{GameError, Item, Slot},
+    packets::{ClickContainer, HashedItem, ReceiveError, SetCarriedItem, send_packet_from_thread},
+};
+
+/// Window ID of the player inventory, which is always open
+pub const PLAYER_WINDOW_ID: i32 = 0;
+
+// Layout of the player inventory window
+#[allow(dead_code)]
+pub const CRAFTING_RESULT_SLOT: usize = 0;
+#[allow(dead_code)]
+pub const CRAFTING_GRID_SLOTS: Range<usize> = 1..5;
+/// Head, chest, legs and feet
+pub const ARMOR_SLOTS: Range<usize> = 5..9;
+pub const MAIN_SLOTS: Range<usize> = 9..36;
+pub const HOTBAR_SLOTS: Range<usize> = 36..45;
+pub const OFFHAND_SLOT: usize = 45;
+const PLAYER_WINDOW_SIZE: usize = 46;
+
+/// Number of slots of the player inventory (main inventory and hotbar) at the end of every
+/// container window
+const PLAYER_SLOTS_IN_CONTAINER: usize = 36;
+
+/// A click in a window
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickAction {
+    /// Pick up, put down or merge the whole stack, swap with the cursor if the items differ
+    LeftClick,
+    /// Pick up half of the stack, or put down one item
+    RightClick,
+    /// Shift click, move the stack between the container and the player inventory
+    QuickMove,
+    /// Swap with a hotbar slot (0 to 8), or with the offhand (40)
+    Swap(u8),
+    /// Drop one item, or the whole stack
+    Throw { whole_stack: bool },
+    /// Double click, gather items of the same kind on the cursor
+    PickupAll,
+}
+
+impl ClickAction {
+    /// Mode and button of the click packet
+    fn mode_and_button(self) -> (i32, i8) {
+        match self {
+            Self::LeftClick => (0, 0),
+            Self::RightClick => (0, 1),
+            Self::QuickMove => (1, 0),
+            Self::Swap(hotbar) => (2, hotbar as i8),
+            Self::Throw { whole_stack } => (4, whole_stack as i8),
+            Self::PickupAll => (6, 0),
+        }
+    }
+}
+
+/// Slot index used by the protocol for clicks outside the window
+const OUTSIDE_SLOT: i16 = -999;
+/// Button of `ClickAction::Swap` for the offhand
+const OFFHAND_BUTTON: u8 = 40;
+
+/// The content of a window, as known by the client
+#[derive(Debug, Clone)]
+pub struct Window {
+    pub id: i32,
+    /// Last state ID sent by the server, sent back with every click
+    pub state_id: i32,
+    pub slots: Vec<Slot>,
+}
+
+#[allow(dead_code)]
+impl Window {
+    pub fn new(id: i32, size: usize) -> Self {
+        Self {
+            id,
+            state_id: 0,
+            slots: vec![Slot::Empty; size],
+        }
+    }
+
+    /// Index of the first slot of the player inventory in this window
+    pub fn player_slots_start(&self) -> usize {
+        if self.id == PLAYER_WINDOW_ID {
+            MAIN_SLOTS.start
+        } else {
+            self.slots.len().saturating_sub(PLAYER_SLOTS_IN_CONTAINER)
+        }
+    }
+
+    pub fn slot(&self, index: usize) -> Option<&Slot> {
+        self.slots.get(index)
+    }
+
+    /// First slot containing `item`
+    pub fn find_item(&self, item: Item) -> Option<usize> {
+        self.slots.iter().position(|s| s.item() == Some(item))
+    }
+
+    pub fn count_item(&self, item: Item) -> i32 {
+        self.slots
+            .iter()
+            .filter(|s| s.item() == Some(item))
+            .map(Slot::count)
+            .sum()
+    }
+
+    /// Index in this window of a hotbar slot
+    pub fn hotbar_slot(&self, hotbar: usize) -> usize {
+        self.player_slots_start() + (MAIN_SLOTS.len() + hotbar)
+    }
+
+    /// Slots a shift click on `index` moves the stack to, and if they are filled from the end
+    fn quick_move_target(&self, index: usize) -> (Range<usize>, bool) {
+        let start = self.player_slots_start();
+        if self.id != PLAYER_WINDOW_ID {
+            return match index < start {
+                true => (start..self.slots.len(), true),
+                false => (0..start, false),
+            };
+        }
+        match index {
+            CRAFTING_RESULT_SLOT => (MAIN_SLOTS.start..HOTBAR_SLOTS.end, true),
+            _ if MAIN_SLOTS.contains(&index) => (HOTBAR_SLOTS, false),
+            _ if HOTBAR_SLOTS.contains(&index) => (MAIN_SLOTS, false),
+            _ => (MAIN_SLOTS.start..HOTBAR_SLOTS.end, false),
+        }
+    }
+
+    /// Move as many items of `index` as possible to `target`, merging with the existing stacks
+    /// first
+    fn move_stack_to(&mut self, index: usize, target: Range<usize>, reverse: bool) {
+        let order: Vec<usize> = match reverse {
+            true => target.rev().collect(),
+            false => target.collect(),
+        };
+
+        for merge in [true, false] {
+            for &i in &order {
+                let stack = &self.slots[index];
+                if stack.is_empty() {
+                    return;
+                }
+                if i == index {
+                    continue;
+                }
+                let other = &self.slots[i];
+                let moved = if merge && other.is_same_item(stack) {
+                    (other.max_stack_size() - other.count()).clamp(0, stack.count())
+                } else if !merge && other.is_empty() {
+                    stack.count().min(stack.max_stack_size())
+                } else {
+                    continue;
+                };
+                let (moved, remaining) = (
+                    stack.with_count(other.count() + moved),
+                    stack.with_count(stack.count() - moved),
+                );
+                self.slots[i] = moved;
+                self.slots[index] = remaining;
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Inventory {
+    pub player: Window,
+    /// Container currently opened, if any
+    pub container: Option<Window>,
+    /// Item held by the mouse
+    pub cursor: Slot,
+    /// Selected hotbar slot (0 to 8)
+    pub selected: usize,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            player: Window::new(PLAYER_WINDOW_ID, PLAYER_WINDOW_SIZE),
+            container: None,
+            cursor: Slot::Empty,
+            selected: 0,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Inventory {
+    pub fn window(&self, window_id: i32) -> Option<&Window> {
+        if window_id == PLAYER_WINDOW_ID {
+            Some(&self.player)
+        } else {
+            self.container.as_ref().filter(|w| w.id == window_id)
+        }
+    }
+
+    pub fn window_mut(&mut self, window_id: i32) -> Option<&mut Window> {
+        if window_id == PLAYER_WINDOW_ID {
+            Some(&mut self.player)
+        } else {
+            self.container.as_mut().filter(|w| w.id == window_id)
+        }
+    }
+
+    /// Window the clicks apply to: the open container, or the player inventory
+    pub fn current_window(&self) -> &Window {
+        self.container.as_ref().unwrap_or(&self.player)
+    }
+
+    pub fn set_content(
+        &mut self,
+        window_id: i32,
+        state_id: i32,
+        mut slots: Vec<Slot>,
+        cursor: Slot,
+    ) {
+        if window_id == PLAYER_WINDOW_ID && slots.len() != PLAYER_WINDOW_SIZE {
+            // The player window is indexed by fixed ranges, its size can't change
+            warn!(
+                "Player window content with {} slots instead of {}",
+                slots.len(),
+                PLAYER_WINDOW_SIZE
+            );
+            slots.resize(PLAYER_WINDOW_SIZE, Slot::Empty);
+        }
+        let window = if window_id == PLAYER_WINDOW_ID {
+            &mut self.player
+        } else {
+            match &mut self.container {
+                Some(window) if window.id == window_id => window,
+                // The container screen may not be tracked, keep the content anyway
+                container => container.insert(Window::new(window_id, 0)),
+            }
+        };
+        window.state_id = state_id;
+        window.slots = slots;
+        self.cursor = cursor;
+        self.sync_player_slots(window_id);
+    }
+
+    pub fn set_slot(&mut self, window_id: i32, state_id: i32, index: usize, slot: Slot) {
+        if window_id == -1 {
+            self.cursor = slot;
+            return;
+        }
+
+        let Some(window) = self.window_mut(window_id) else {
+            warn!("Slot update for unknown window {}", window_id);
+            return;
+        };
+        let Some(old) = window.slots.get_mut(index) else {
+            warn!("Slot update out of window {} ({})", window_id, index);
+            return;
+        };
+        *old = slot;
+        window.state_id = state_id;
+        self.sync_player_slots(window_id);
+    }
+
+    /// Set a slot of the player inventory, using the indices of the player inventory itself
+    /// (0-8 hotbar, 9-35 main, 36-39 armor from feet to head, 40 offhand)
+    pub fn set_player_slot(&mut self, index: usize, slot: Slot) {
+        let window_index = match index {
+            0..9 => HOTBAR_SLOTS.start + index,
+            9..36 => index,
+            36..40 => ARMOR_SLOTS.end - 1 - (index - 36),
+            40 => OFFHAND_SLOT,
+            _ => {
+                warn!("Player inventory slot out of range ({})", index);
+                return;
+            }
+        };
+        self.player.slots[window_index] = slot;
+        self.sync_player_slots(PLAYER_WINDOW_ID);
+    }
+
+    /// The main inventory and hotbar are shared between the player window and containers
+    fn sync_player_slots(&mut self, from: i32) {
+        let Some(container) = self.container.as_mut() else {
+            return;
+        };
+        let start = container.player_slots_start();
+        if container.slots.len() < PLAYER_SLOTS_IN_CONTAINER {
+            return;
+        }
+        let player_slots = &mut self.player.slots[MAIN_SLOTS.start..HOTBAR_SLOTS.end];
+        let container_slots = &mut container.slots[start..];
+        if from == PLAYER_WINDOW_ID {
+            container_slots.clone_from_slice(player_slots);
+        } else {
+            player_slots.clone_from_slice(container_slots);
+        }
+    }
+
+    pub fn set_selected(&mut self, selected: usize) {
+        if selected >= HOTBAR_SLOTS.len() {
+            warn!("Selected hotbar slot out of range ({})", selected);
+            return;
+        }
+        self.selected = selected;
+    }
+
+    /// Index in the player window of a hotbar slot
+    pub fn hotbar_slot(hotbar: usize) -> usize {
+        HOTBAR_SLOTS.start + hotbar
+    }
+
+    pub fn held_item(&self) -> &Slot {
+        &self.player.slots[Self::hotbar_slot(self.selected)]
+    }
+
+    pub fn offhand(&self) -> &Slot {
+        &self.player.slots[OFFHAND_SLOT]
+    }
+
+    /// Slot of the player window containing `item`, hotbar first
+    pub fn find_item(&self, item: Item) -> Option<usize> {
+        HOTBAR_SLOTS
+            .chain(MAIN_SLOTS)
+            .chain([OFFHAND_SLOT])
+            .find(|&i| self.player.slots[i].item() == Some(item))
+    }
+
+    /// Hotbar slot (0 to 8) containing `item`
+    pub fn find_in_hotbar(&self, item: Item) -> Option<usize> {
+        self.player.slots[HOTBAR_SLOTS]
+            .iter()
+            .position(|s| s.item() == Some(item))
+    }
+
+    /// Number of `item` carried, in the main inventory, hotbar and offhand
+    pub fn count_item(&self, item: Item) -> i32 {
+        MAIN_SLOTS
+            .chain(HOTBAR_SLOTS)
+            .chain([OFFHAND_SLOT])
+            .map(|i| &self.player.slots[i])
+            .filter(|s| s.item() == Some(item))
+            .map(Slot::count)
+            .sum()
+    }
+
+    /// Apply a click locally, the way the server is expected to
+    fn predict_click(
+        &mut self,
+        window_id: i32,
+        index: Option<usize>,
+        action: ClickAction,
+    ) -> Result<(), GameError> {
+        let (window, offhand) = match &mut self.container {
+            _ if window_id == PLAYER_WINDOW_ID => (&mut self.player, None),
+            Some(container) if container.id == window_id => {
+                (container, Some(&mut self.player.slots[OFFHAND_SLOT]))
+            }
+            _ => return Err(GameError::UnknownWindow(window_id)),
+        };
+        let cursor = &mut self.cursor;
+
+        let Some(index) = index else {
+            // Outside of the window: drop the cursor
+            *cursor = match action {
+                ClickAction::LeftClick => Slot::Empty,
+                ClickAction::RightClick => cursor.with_count(cursor.count() - 1),
+                _ => cursor.clone(),
+            };
+            return Ok(());
+        };
+        if index >= window.slots.len() {
+            return Err(GameError::InvalidSlot(index));
+        }
+
+        match action {
+            ClickAction::LeftClick | ClickAction::RightClick => {
+                let slot = &mut window.slots[index];
+                let room = slot.max_stack_size() - slot.count();
+                if cursor.is_empty() {
+                    let taken = match action {
+                        ClickAction::LeftClick => slot.count(),
+                        _ => (slot.count() + 1) / 2,
+                    };
+                    *cursor = slot.with_count(taken);
+                    *slot = slot.with_count(slot.count() - taken);
+                } else if slot.is_empty() || slot.is_same_item(cursor) {
+                    let put = match action {
+                        ClickAction::LeftClick => cursor.count(),
+                        _ => 1,
+                    };
+                    let put = if slot.is_empty() {
+                        put.min(cursor.max_stack_size())
+                    } else {
+                        put.min(room).max(0)
+                    };
+                    *slot = cursor.with_count(slot.count() + put);
+                    *cursor = cursor.with_count(cursor.count() - put);
+                } else {
+                    std::mem::swap(slot, cursor);
+                }
+            }
+            ClickAction::QuickMove => {
+                let (target, reverse) = window.quick_move_target(index);
+                window.move_stack_to(index, target, reverse);
+            }
+            ClickAction::Swap(OFFHAND_BUTTON) => match offhand {
+                Some(offhand) => std::mem::swap(&mut window.slots[index], offhand),
+                None => window.slots.swap(index, OFFHAND_SLOT),
+            },
+            ClickAction::Swap(hotbar) => {
+                let hotbar = window.hotbar_slot(hotbar as usize);
+                if hotbar >= window.slots.len() {
+                    return Err(GameError::InvalidSlot(hotbar));
+                }
+                window.slots.swap(index, hotbar);
+            }
+            ClickAction::Throw { whole_stack } => {
+                // Only possible with an empty cursor
+                if cursor.is_empty() {
+                    let slot = &mut window.slots[index];
+                    *slot = match whole_stack {
+                        true => Slot::Empty,
+                        false => slot.with_count(slot.count() - 1),
+                    };
+                }
+            }
+            ClickAction::PickupAll => {
+                // Partial stacks are taken first
+                for full in [false, true] {
+                    for slot in &mut window.slots {
+                        let room = cursor.max_stack_size() - cursor.count();
+                        if room <= 0 {
+                            break;
+                        }
+                        if slot.is_same_item(cursor)
+                            && (slot.count() >= slot.max_stack_size()) == full
+                        {
+                            let taken = slot.count().min(room);
+                            *cursor = cursor.with_count(cursor.count() + taken);
+                            *slot = slot.with_count(slot.count() - taken);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Click in a window, `index` being `None` for a click outside of it.
+    ///
+    /// The result is predicted and applied to the local inventory immediately. If the server
+    /// disagrees, it sends the content of the window back, which replaces the prediction.
+    pub fn click(
+        &mut self,
+        window_id: i32,
+        index: Option<usize>,
+        action: ClickAction,
+    ) -> Result<(), ReceiveError> {
+        let window = self
+            .window(window_id)
+            .ok_or(GameError::UnknownWindow(window_id))?;
+        let state_id = window.state_id;
+        let before = window.slots.clone();
+
+        self.predict_click(window_id, index, action)?;
+
+        let window = self.window(window_id).expect("The window exists");
+        let changed_slots = before
+            .iter()
+            .zip(&window.slots)
+            .enumerate()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(i, (_, after))| (i as i16, HashedItem::from_slot(after)))
+            .collect();
+        let (mode, button) = action.mode_and_button();
+
+        send_packet_from_thread(ClickContainer {
+            window_id: VarInt(window_id),
+            state_id: VarInt(state_id),
+            slot: index.map_or(OUTSIDE_SLOT, |i| i as i16),
+            button,
+            mode: VarInt(mode),
+            changed_slots,
+            carried_item: HashedItem::from_slot(&self.cursor),
+        })?;
+
+        self.sync_player_slots(window_id);
+        Ok(())
+    }
+
+    /// Click in the open container, or in the player inventory
+    pub fn click_current(
+        &mut self,
+        index: Option<usize>,
+        action: ClickAction,
+    ) -> Result<(), ReceiveError> {
+        self.click(self.current_window().id, index, action)
+    }
+
+    /// Swap a slot of the current window with a hotbar slot (0 to 8)
+    pub fn swap_with_hotbar(&mut self, index: usize, hotbar: usize) -> Result<(), ReceiveError> {
+        if hotbar >= HOTBAR_SLOTS.len() {
+            return Err(GameError::InvalidSlot(hotbar).into());
+        }
+        self.click_current(Some(index), ClickAction::Swap(hotbar as u8))
+    }
+
+    /// Shift click a slot of the current window
+    pub fn quick_move(&mut self, index: usize) -> Result<(), ReceiveError> {
+        self.click_current(Some(index), ClickAction::QuickMove)
+    }
+
+    /// Drop one item or the whole stack of a slot of the current window
+    pub fn drop_slot(&mut self, index: usize, whole_stack: bool) -> Result<(), ReceiveError> {
+        self.click_current(Some(index), ClickAction::Throw { whole_stack })
+    }
+
+    /// Pick up half of a stack of the current window, then put it down in `to`
+    pub fn split_stack(&mut self, from: usize, to: usize) -> Result<(), ReceiveError> {
+        self.click_current(Some(from), ClickAction::RightClick)?;
+        self.click_current(Some(to), ClickAction::LeftClick)
+    }
+
+    /// Drop the item held by the cursor
+    pub fn drop_cursor(&mut self) -> Result<(), ReceiveError> {
+        self.click_current(None, ClickAction::LeftClick)
+    }
+
+    /// Change the selected hotbar slot (0 to 8)
+    pub fn select_hotbar(&mut self, hotbar: usize) -> Result<(), ReceiveError> {
+        if hotbar >= HOTBAR_SLOTS.len() {
+            return Err(GameError::InvalidSlot(hotbar).into());
+        }
+        send_packet_from_thread(SetCarriedItem {
+            slot: hotbar as i16,
+        })?;
+        self.selected = hotbar;
+        Ok(())
+    }
+
+    /// First empty slot of the main inventory or hotbar
+    pub fn find_empty(&self) -> Option<usize> {
+        HOTBAR_SLOTS
+            .chain(MAIN_SLOTS)
+            .find(|&i| self.player.slots[i].is_empty())
+    }
+}
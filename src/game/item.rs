@@ -0,0 +1,79 @@
+use std::fmt::{Debug, Display};
+
+#[derive(Debug)]
+pub struct ItemInfo {
+    pub name: &'static str,
+    /// Default value of the `max_stack_size` component
+    pub max_stack_size: i32,
+    /// Default value of the `max_damage` component, 0 if the item can't be damaged
+    pub max_damage: i32,
+    /// Default value of the `tool` component
+    pub tool: Option<ToolInfo>,
+}
+
+#[derive(Debug)]
+pub struct ToolInfo {
+    pub rules: &'static [ToolRuleInfo],
+    pub default_mining_speed: f32,
+}
+
+#[derive(Debug)]
+pub struct ToolRuleInfo {
+    /// Block names, or a single tag starting with `#`
+    pub blocks: &'static [&'static str],
+    pub speed: Option<f32>,
+    pub correct_for_drops: Option<bool>,
+}
+
+include!(concat!(env!("OUT_DIR"), "/items.rs"));
+
+/// An item ID of the `minecraft:item` registry
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Item(pub i32);
+
+impl Item {
+    pub const AIR: Self = Self(0);
+
+    pub fn info(self) -> Option<&'static ItemInfo> {
+        usize::try_from(self.0).ok().and_then(|id| ITEMS.get(id))
+    }
+
+    /// Name of the item (e.g. `minecraft:stick`), `minecraft:unknown` if not in the table
+    pub fn name(self) -> &'static str {
+        self.info().map_or("minecraft:unknown", |i| i.name)
+    }
+
+    /// Accept `minecraft:stick` and `stick`
+    #[allow(dead_code)]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        ITEMS
+            .iter()
+            .position(|i| i.name.strip_prefix("minecraft:") == Some(name))
+            .map(|id| Self(id as i32))
+    }
+
+    pub fn max_stack_size(self) -> i32 {
+        self.info().map_or(64, |i| i.max_stack_size)
+    }
+
+    pub fn max_damage(self) -> i32 {
+        self.info().map_or(0, |i| i.max_damage)
+    }
+
+    pub fn tool(self) -> Option<&'static ToolInfo> {
+        self.info().and_then(|i| i.tool.as_ref())
+    }
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Debug for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Item({}, {})", self.0, self)
+    }
+}
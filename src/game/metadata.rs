@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use macros::Deserialize;
+
+use crate::{
+    datatypes::{BlockPos, TextComponent, VarInt, VarLong},
+    game::{Entity, EntityType, Slot, Vec3},
+    nbt::Nbt,
+};
+
+/// Value of an entity metadata field, by serializer ID
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[repr(u32)]
+#[enum_repr(VarInt)]
+pub enum MetadataValue {
+    Byte(i8) = 0,
+    VarInt(VarInt),
+    VarLong(VarLong),
+    Float(f32),
+    String(String),
+    TextComponent(TextComponent),
+    OptionalTextComponent(Option<TextComponent>),
+    Slot(Slot),
+    Boolean(bool),
+    Rotations(Vec3<f32>),
+    Position(BlockPos),
+    OptionalPosition(Option<BlockPos>),
+    Direction(VarInt),
+    /// UUID of a living entity
+    OptionalLivingEntity(Option<u128>),
+    BlockState(VarInt),
+    /// 0 for no block, the block state otherwise
+    OptionalBlockState(VarInt),
+    /// Entities on the shoulders of players
+    Nbt(Nbt),
+    Particle(Particle),
+    Particles(Vec<Particle>),
+    VillagerData {
+        villager_type: VarInt,
+        profession: VarInt,
+        level: VarInt,
+    },
+    /// 0 for no value, the value plus one otherwise
+    OptionalVarInt(VarInt),
+    Pose(VarInt),
+    CatVariant(VarInt),
+    CowVariant(VarInt),
+    WolfVariant(VarInt),
+    WolfSoundVariant(VarInt),
+    FrogVariant(VarInt),
+    PigVariant(VarInt),
+    ChickenVariant(VarInt),
+    /// Dimension and position
+    OptionalGlobalPosition(Option<(String, BlockPos)>),
+    PaintingVariant(VarInt),
+    SnifferState(VarInt),
+    ArmadilloState(VarInt),
+    Vector3(Vec3<f32>),
+    Quaternion([f32; 4]),
+}
+
+/// Metadata fields of an entity, by index
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityMetadata(pub HashMap<u8, MetadataValue>);
+
+impl EntityMetadata {
+    #[allow(dead_code)]
+    pub fn get(&self, index: u8) -> Option<&MetadataValue> {
+        self.0.get(&index)
+    }
+
+    /// Apply the fields sent by the server, the others keep their value
+    pub fn update(&mut self, fields: EntityMetadata) {
+        self.0.extend(fields.0);
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pose {
+    #[default]
+    Standing,
+    FallFlying,
+    Sleeping,
+    Swimming,
+    SpinAttack,
+    Crouching,
+    LongJumping,
+    Dying,
+    Croaking,
+    UsingTongue,
+    Sitting,
+    Roaring,
+    Sniffing,
+    Emerging,
+    Digging,
+    Sliding,
+    Shooting,
+    Inhaling,
+}
+
+#[allow(dead_code)]
+impl Pose {
+    pub fn from_id(id: i32) -> Option<Self> {
+        Some(match id {
+            0 => Self::Standing,
+            1 => Self::FallFlying,
+            2 => Self::Sleeping,
+            3 => Self::Swimming,
+            4 => Self::SpinAttack,
+            5 => Self::Crouching,
+            6 => Self::LongJumping,
+            7 => Self::Dying,
+            8 => Self::Croaking,
+            9 => Self::UsingTongue,
+            10 => Self::Sitting,
+            11 => Self::Roaring,
+            12 => Self::Sniffing,
+            13 => Self::Emerging,
+            14 => Self::Digging,
+            15 => Self::Sliding,
+            16 => Self::Shooting,
+            17 => Self::Inhaling,
+            _ => return None,
+        })
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[repr(u32)]
+#[enum_repr(VarInt)]
+pub enum PositionSource {
+    Block(BlockPos) = 0,
+    Entity { entity_id: VarInt, eye_height: f32 },
+}
+
+/// Options of a particle, depending on its type
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParticleData {
+    None,
+    BlockState(VarInt),
+    Dust {
+        /// RGB color
+        color: i32,
+        scale: f32,
+    },
+    DustColorTransition {
+        from: i32,
+        to: i32,
+        scale: f32,
+    },
+    /// ARGB color
+    Color(i32),
+    Spell {
+        color: i32,
+        power: f32,
+    },
+    SculkCharge {
+        roll: f32,
+    },
+    Item(Slot),
+    Vibration {
+        source: PositionSource,
+        ticks: VarInt,
+    },
+    Trail {
+        target: Vec3<f64>,
+        color: i32,
+        duration: VarInt,
+    },
+    Shriek {
+        delay: VarInt,
+    },
+}
+
+/// A particle, by ID in the `minecraft:particle_type` registry
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Particle {
+    pub id: i32,
+    pub data: ParticleData,
+}
+
+/// Indices of the common metadata fields, which depend on the class hierarchy of the entity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct MetadataLayout {
+    health: Option<u8>,
+    baby: Option<u8>,
+    item: Option<u8>,
+    sheep_wool: Option<u8>,
+}
+
+impl MetadataLayout {
+    fn of(entity_type: EntityType) -> Self {
+        use EntityType::*;
+
+        if !entity_type.is_living() {
+            return Self {
+                item: (entity_type == Item).then_some(8),
+                ..Default::default()
+            };
+        }
+        let baby = match entity_type {
+            // Animals, villagers and other entities with a baby variant
+            Armadillo | Axolotl | Bee | Camel | Cat | Chicken | Cow | Dolphin | Donkey | Fox
+            | Frog | HappyGhast | GlowSquid | Goat | Hoglin | Horse | Llama | Mooshroom | Mule
+            | Ocelot | Panda | Parrot | Pig | PolarBear | Rabbit | Sheep | SkeletonHorse
+            | Sniffer | Squid | Strider | TraderLlama | Turtle | Villager | WanderingTrader
+            | Wolf | ZombieHorse => Some(16),
+            Zombie | Husk | Drowned | ZombieVillager | ZombifiedPiglin | Zoglin => Some(16),
+            Piglin => Some(17),
+            _ => None,
+        };
+        Self {
+            health: Some(9),
+            baby,
+            item: None,
+            sheep_wool: (entity_type == Sheep).then_some(17),
+        }
+    }
+}
+
+/// Base entity flags, in the first metadata field
+#[allow(dead_code)]
+pub mod entity_flags {
+    pub const ON_FIRE: i8 = 0x01;
+    pub const CROUCHING: i8 = 0x02;
+    pub const SPRINTING: i8 = 0x08;
+    pub const SWIMMING: i8 = 0x10;
+    pub const INVISIBLE: i8 = 0x20;
+    pub const GLOWING: i8 = 0x40;
+    pub const FLYING_WITH_ELYTRA: i8 = -0x80;
+}
+
+#[allow(dead_code)]
+impl Entity {
+    fn metadata_field(&self, index: Option<u8>) -> Option<&MetadataValue> {
+        self.metadata.get(index?)
+    }
+
+    pub fn flags(&self) -> i8 {
+        match self.metadata.get(0) {
+            Some(MetadataValue::Byte(flags)) => *flags,
+            _ => 0,
+        }
+    }
+
+    pub fn has_flag(&self, flag: i8) -> bool {
+        self.flags() & flag != 0
+    }
+
+    pub fn pose(&self) -> Pose {
+        match self.metadata.get(6) {
+            Some(MetadataValue::Pose(pose)) => Pose::from_id(pose.0).unwrap_or_default(),
+            _ => Pose::Standing,
+        }
+    }
+
+    pub fn custom_name(&self) -> Option<&TextComponent> {
+        match self.metadata.get(2) {
+            Some(MetadataValue::OptionalTextComponent(name)) => name.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Health of living entities, the server only sends it for some of them
+    pub fn health(&self) -> Option<f32> {
+        match self.metadata_field(MetadataLayout::of(self.entity_type).health) {
+            Some(MetadataValue::Float(health)) => Some(*health),
+            _ => None,
+        }
+    }
+
+    /// Stack of a dropped item
+    pub fn item(&self) -> Option<&Slot> {
+        match self.metadata_field(MetadataLayout::of(self.entity_type).item) {
+            Some(MetadataValue::Slot(slot)) => Some(slot),
+            _ => None,
+        }
+    }
+
+    pub fn is_baby(&self) -> bool {
+        matches!(
+            self.metadata_field(MetadataLayout::of(self.entity_type).baby),
+            Some(MetadataValue::Boolean(true))
+        )
+    }
+
+    /// Wool color of a sheep, by ID in the dye colors
+    pub fn sheep_color(&self) -> Option<u8> {
+        match self.metadata_field(MetadataLayout::of(self.entity_type).sheep_wool) {
+            Some(MetadataValue::Byte(wool)) => Some(*wool as u8 & 0x0f),
+            None if self.entity_type == EntityType::Sheep => Some(0),
+            _ => None,
+        }
+    }
+
+    pub fn is_sheared(&self) -> bool {
+        matches!(
+            self.metadata_field(MetadataLayout::of(self.entity_type).sheep_wool),
+            Some(MetadataValue::Byte(wool)) if wool & 0x10 != 0
+        )
+    }
+}
@@ -0,0 +1,130 @@
+use crate::game::{
+    ChunkPos, Entity, EntityCategory, EntityType, Game, Vec3d, block_containing, world::World,
+};
+
+/// Ticks to reach the position sent by the server, like the vanilla client
+const INTERPOLATION_TICKS: u8 = 3;
+/// Horizontal drag multiplier on the ground, from the friction of most blocks
+const GROUND_FRICTION: f64 = 0.6;
+
+/// How an entity type moves on its own, per tick
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motion {
+    /// Removed from the vertical speed
+    pub gravity: f64,
+    /// Multiplier of the speed
+    pub drag: f64,
+}
+
+impl EntityType {
+    /// Gravity and drag of the entity type, `None` for the ones which don't move on their own
+    pub fn motion(self) -> Option<Motion> {
+        use EntityType::*;
+
+        let (gravity, drag) = match self {
+            Item | FallingBlock | Tnt => (0.04, 0.98),
+            ExperienceOrb => (0.03, 0.98),
+            Arrow | SpectralArrow | Trident => (0.05, 0.99),
+            Snowball | Egg | EnderPearl => (0.03, 0.99),
+            SplashPotion | LingeringPotion => (0.05, 0.99),
+            ExperienceBottle => (0.07, 0.99),
+            LlamaSpit => (0.06, 0.99),
+            FishingBobber => (0.03, 0.92),
+            Fireball | SmallFireball | DragonFireball | WitherSkull | WindCharge
+            | BreezeWindCharge | ShulkerBullet => (0., 0.95),
+            _ => match self.category() {
+                EntityCategory::Hostile | EntityCategory::Passive | EntityCategory::Player => {
+                    (0.08, 0.91)
+                }
+                EntityCategory::Vehicle => (0.04, 0.95),
+                _ if self == ArmorStand => (0.08, 0.91),
+                _ => return None,
+            },
+        };
+        Some(Motion { gravity, drag })
+    }
+}
+
+/// Height of the top of the blocks under `pos`, if its feet are in one
+fn floor_height(world: &World, pos: Vec3d) -> Option<f64> {
+    let block = block_containing(pos);
+    let relative = Vec3d {
+        x: pos.x - block.0.x as f64,
+        y: pos.y - block.0.y as f64,
+        z: pos.z - block.0.z as f64,
+    };
+    world
+        .collision_boxes(block)
+        .iter()
+        .filter(|b| {
+            (b.min.x..=b.max.x).contains(&relative.x)
+                && (b.min.z..=b.max.z).contains(&relative.z)
+                && b.min.y <= relative.y
+        })
+        .map(|b| block.0.y as f64 + b.max.y)
+        .reduce(f64::max)
+        .filter(|top| *top >= pos.y)
+}
+
+impl Entity {
+    /// The server sent the position of the entity, the estimate moves to it over a few ticks
+    pub fn set_server_position(&mut self, pos: Vec3d) {
+        self.server_position = pos;
+        self.interpolation_ticks = INTERPOLATION_TICKS;
+    }
+
+    /// Move the entity to `pos` at once, like after a teleport
+    pub fn teleport(&mut self, pos: Vec3d) {
+        self.server_position = pos;
+        self.position = pos;
+        self.interpolation_ticks = 0;
+    }
+
+    /// Estimate the position of the entity one tick later
+    fn tick_motion(&mut self, world: &World) {
+        if self.interpolation_ticks > 0 {
+            let delta = self.server_position - self.position;
+            self.position += delta * (1. / self.interpolation_ticks as f64);
+            self.interpolation_ticks -= 1;
+            return;
+        }
+        let Some(motion) = self.entity_type.motion() else {
+            return;
+        };
+        // Without the blocks, the entity would fall forever
+        if !world.is_chunk_loaded(ChunkPos::from_block_pos(block_containing(self.position))) {
+            return;
+        }
+
+        self.position += self.speed;
+        if let Some(floor) = floor_height(world, self.position) {
+            self.position.y = floor;
+            self.speed.y = 0.;
+            self.on_ground = true;
+        } else if self.speed.y != 0. {
+            self.on_ground = false;
+        }
+
+        let horizontal_drag = if self.on_ground {
+            motion.drag * GROUND_FRICTION
+        } else {
+            motion.drag
+        };
+        self.speed.x *= horizontal_drag;
+        self.speed.z *= horizontal_drag;
+        if !self.on_ground {
+            self.speed.y = (self.speed.y - motion.gravity) * 0.98;
+        }
+    }
+}
+
+impl Game {
+    /// Move the entities between the updates of the server, called each tick
+    pub fn tick_entities(&self) {
+        for (id, entity) in self.entities.all() {
+            if id != self.player.entity_id {
+                entity.write().tick_motion(&self.world);
+            }
+        }
+    }
+}
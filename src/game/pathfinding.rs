@@ -0,0 +1,395 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    sync::mpsc::{Receiver, channel},
+    thread,
+};
+
+use crate::{
+    datatypes::BlockPos,
+    game::{BlockState, Game, GameError, Vec3i, world::World},
+};
+
+/// Cost of each kind of move, roughly the number of ticks it takes
+#[derive(Debug, Clone, Copy)]
+pub struct PathCosts {
+    pub walk: f32,
+    pub jump: f32,
+    /// Added for each block fallen
+    pub fall: f32,
+    pub swim: f32,
+}
+
+impl PathCosts {
+    /// Lowest cost of moving one block horizontally: falls cost at least a walk, and only vertical
+    /// moves, which are free for the heuristic, are cheaper
+    fn min_horizontal(&self) -> f32 {
+        self.walk.min(self.jump).min(self.swim)
+    }
+}
+
+impl Default for PathCosts {
+    fn default() -> Self {
+        Self {
+            walk: 4.6,
+            jump: 8.,
+            fall: 1.,
+            swim: 9.,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PathOptions {
+    pub costs: PathCosts,
+    /// Nodes explored before giving up
+    pub max_nodes: usize,
+    /// Highest drop taken without water below, 3 blocks don't hurt
+    pub max_fall: i32,
+    /// The goal is reached within this distance, in blocks
+    pub range: f32,
+    /// Return the path to the node closest to the goal if the goal can't be reached
+    pub partial: bool,
+}
+
+impl Default for PathOptions {
+    fn default() -> Self {
+        Self {
+            costs: PathCosts::default(),
+            max_nodes: 10_000,
+            max_fall: 3,
+            range: 0.,
+            partial: false,
+        }
+    }
+}
+
+/// How a node of a path is reached from the previous one
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathMove {
+    Start,
+    Walk,
+    /// Jump up one block
+    Jump,
+    /// Walk off an edge and fall
+    Fall,
+    Swim,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathNode {
+    /// Block the feet are in
+    pub pos: BlockPos,
+    pub movement: PathMove,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct Path {
+    /// From the start to the goal, the start included
+    pub nodes: Vec<PathNode>,
+    /// False for a partial path ending at the closest node to the goal
+    pub complete: bool,
+}
+
+const HORIZONTAL: [Vec3i; 4] = [
+    Vec3i { x: 0, y: 0, z: -1 },
+    Vec3i { x: 0, y: 0, z: 1 },
+    Vec3i { x: -1, y: 0, z: 0 },
+    Vec3i { x: 1, y: 0, z: 0 },
+];
+const UP: Vec3i = Vec3i { x: 0, y: 1, z: 0 };
+const DOWN: Vec3i = Vec3i { x: 0, y: -1, z: 0 };
+
+fn is_dangerous(state: BlockState) -> bool {
+    matches!(
+        state.name(),
+        "minecraft:lava"
+            | "minecraft:fire"
+            | "minecraft:soul_fire"
+            | "minecraft:campfire"
+            | "minecraft:soul_campfire"
+            | "minecraft:magma_block"
+            | "minecraft:cactus"
+            | "minecraft:sweet_berry_bush"
+            | "minecraft:powder_snow"
+            | "minecraft:cobweb"
+            | "minecraft:wither_rose"
+    )
+}
+
+/// Walkability of the blocks, from the collision shapes.
+///
+/// The feet of the player are always at the bottom of a block: blocks lower than a full block
+/// (slabs, carpets...) can't be stood on, which only makes some paths longer.
+struct Terrain<'a> {
+    world: &'a World,
+}
+
+impl Terrain<'_> {
+    fn block(&self, pos: BlockPos) -> Option<BlockState> {
+        self.world.block_at(pos)
+    }
+
+    fn is_passable(&self, pos: BlockPos) -> bool {
+        let Some(state) = self.block(pos) else {
+            return false;
+        };
+        // Fences and walls stick out in the block above them
+        let below = self.world.collision_boxes(BlockPos(pos.0 + DOWN));
+        state.collision_boxes().is_empty()
+            && !is_dangerous(state)
+            && below.iter().all(|b| b.max.y <= 1.)
+    }
+
+    fn is_floor(&self, pos: BlockPos) -> bool {
+        let Some(state) = self.block(pos) else {
+            return false;
+        };
+        let top = state
+            .collision_boxes()
+            .iter()
+            .map(|b| b.max.y)
+            .fold(0., f64::max);
+        (0.9..=1.).contains(&top) && !is_dangerous(state)
+    }
+
+    fn is_water(&self, pos: BlockPos) -> bool {
+        self.block(pos).is_some_and(BlockState::is_water)
+    }
+
+    /// The player fits in `pos` with its head above
+    fn fits(&self, pos: BlockPos) -> bool {
+        self.is_passable(pos) && self.is_passable(BlockPos(pos.0 + UP))
+    }
+
+    fn can_stand(&self, pos: BlockPos) -> bool {
+        self.fits(pos) && (self.is_floor(BlockPos(pos.0 + DOWN)) || self.is_water(pos))
+    }
+
+    /// Nodes reachable from `pos` with their cost
+    fn neighbours(&self, pos: BlockPos, options: &PathOptions) -> Vec<(BlockPos, PathMove, f32)> {
+        let costs = options.costs;
+        let mut res = Vec::new();
+        let in_water = self.is_water(pos);
+        let move_cost = |to: BlockPos| {
+            if self.is_water(to) {
+                costs.swim
+            } else {
+                costs.walk
+            }
+        };
+
+        for dir in HORIZONTAL {
+            let next = BlockPos(pos.0 + dir);
+            if self.can_stand(next) {
+                let movement = if in_water {
+                    PathMove::Swim
+                } else {
+                    PathMove::Walk
+                };
+                res.push((next, movement, move_cost(next)));
+                continue;
+            }
+
+            let up = BlockPos(next.0 + UP);
+            let head_room = BlockPos(pos.0 + UP + UP);
+            if self.can_stand(up) && self.is_passable(head_room) {
+                res.push((up, PathMove::Jump, costs.jump));
+                continue;
+            }
+
+            if !self.fits(next) {
+                continue;
+            }
+            let mut below = next;
+            for height in 1..=options.max_fall + 1 {
+                below = BlockPos(below.0 + DOWN);
+                let lands_in_water = self.is_water(below);
+                if height > options.max_fall && !lands_in_water {
+                    break;
+                }
+                if self.can_stand(below) {
+                    let cost = costs.walk + costs.fall * height as f32;
+                    res.push((below, PathMove::Fall, cost));
+                    break;
+                }
+                if !self.is_passable(below) {
+                    break;
+                }
+            }
+        }
+
+        // Diagonals, both sides have to be free not to cut corners
+        for a in &HORIZONTAL[..2] {
+            for b in &HORIZONTAL[2..] {
+                let next = BlockPos(pos.0 + *a + *b);
+                let side_a = BlockPos(pos.0 + *a);
+                let side_b = BlockPos(pos.0 + *b);
+                if self.can_stand(next) && self.fits(side_a) && self.fits(side_b) {
+                    let movement = if in_water {
+                        PathMove::Swim
+                    } else {
+                        PathMove::Walk
+                    };
+                    res.push((next, movement, move_cost(next) * std::f32::consts::SQRT_2));
+                }
+            }
+        }
+
+        if in_water {
+            let up = BlockPos(pos.0 + UP);
+            if self.fits(up) && (self.is_water(up) || self.can_stand(up)) {
+                res.push((up, PathMove::Swim, costs.swim));
+            }
+            let down = BlockPos(pos.0 + DOWN);
+            if self.is_water(down) && self.is_passable(down) {
+                res.push((down, PathMove::Swim, costs.swim));
+            }
+        }
+        res
+    }
+}
+
+impl BlockPos {
+    pub fn distance(self, other: Self) -> f32 {
+        let d = self.0 - other.0;
+        ((d.x * d.x + d.y * d.y + d.z * d.z) as f32).sqrt()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct OpenNode {
+    /// Cost from the start plus the estimated cost to the goal
+    estimate: f32,
+    pos: BlockPos,
+}
+
+impl Eq for OpenNode {}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, the heap pops the lowest estimate
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A* search of a path from `start` to `goal` in the loaded chunks
+pub fn find_path(
+    world: &World,
+    start: BlockPos,
+    goal: BlockPos,
+    options: &PathOptions,
+) -> Result<Path, GameError> {
+    let terrain = Terrain { world };
+    // Never more than the actual cost, so that the first path found is the cheapest one
+    let heuristic = |pos: BlockPos| {
+        let d = pos.0 - goal.0;
+        let horizontal = ((d.x * d.x + d.z * d.z) as f32).sqrt();
+        (horizontal - options.range).max(0.) * options.costs.min_horizontal()
+    };
+
+    let mut open = BinaryHeap::new();
+    // Cost from the start and previous node
+    let mut visited: HashMap<BlockPos, (f32, Option<BlockPos>, PathMove)> = HashMap::new();
+    visited.insert(start, (0., None, PathMove::Start));
+    open.push(OpenNode {
+        estimate: heuristic(start),
+        pos: start,
+    });
+
+    let mut closest = (start.distance(goal), start);
+    let mut explored = 0;
+    let reached = loop {
+        let Some(OpenNode { estimate, pos }) = open.pop() else {
+            break None;
+        };
+        let cost = visited[&pos].0;
+        if estimate > cost + heuristic(pos) {
+            // Outdated entry, a cheaper way to this node was found
+            continue;
+        }
+
+        let dist = pos.distance(goal);
+        if dist <= options.range {
+            break Some(pos);
+        }
+        if dist < closest.0 {
+            closest = (dist, pos);
+        }
+
+        explored += 1;
+        if explored >= options.max_nodes {
+            break None;
+        }
+
+        for (next, movement, move_cost) in terrain.neighbours(pos, options) {
+            let next_cost = cost + move_cost;
+            if visited.get(&next).is_some_and(|(c, ..)| *c <= next_cost) {
+                continue;
+            }
+            visited.insert(next, (next_cost, Some(pos), movement));
+            open.push(OpenNode {
+                estimate: next_cost + heuristic(next),
+                pos: next,
+            });
+        }
+    };
+
+    let (end, complete) = match reached {
+        Some(pos) => (pos, true),
+        None if options.partial && closest.1 != start => (closest.1, false),
+        None if explored >= options.max_nodes => {
+            return Err(GameError::PathNodeLimit(options.max_nodes));
+        }
+        None => return Err(GameError::NoPath(goal)),
+    };
+
+    let mut nodes = Vec::new();
+    let mut current = Some(end);
+    while let Some(pos) = current {
+        let (_, previous, movement) = visited[&pos];
+        nodes.push(PathNode { pos, movement });
+        current = previous;
+    }
+    nodes.reverse();
+    Ok(Path { nodes, complete })
+}
+
+impl Game {
+    /// Block the feet of the player are in
+    pub fn player_block_pos(&self) -> BlockPos {
+        let pos = self.player.entity.read().position;
+        BlockPos(Vec3i {
+            x: pos.x.floor() as i32,
+            // Standing on a block whose top is slightly below 1, like farmland
+            y: (pos.y + 0.1).floor() as i32,
+            z: pos.z.floor() as i32,
+        })
+    }
+
+    /// Search a path from the player to `goal` in another thread, the receiver gets the result
+    #[allow(dead_code)]
+    pub fn find_path(
+        &self,
+        goal: BlockPos,
+        options: PathOptions,
+    ) -> Receiver<Result<Path, GameError>> {
+        let world = self.world.clone();
+        let start = self.player_block_pos();
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            // The requester may have given up waiting
+            let _ = sender.send(find_path(&world, start, goal, &options));
+        });
+        receiver
+    }
+}
@@ -0,0 +1,448 @@
+use crate::{
+    datatypes::BlockPos,
+    game::{
+        Aabb, BlockState, ChunkPos, Game, GameMode, Rotation, Vec3d, Vec3i, player::effects,
+        world::World,
+    },
+    packets::{
+        PlayerPosFlags, ReceiveError, SetPlayerMovementFlags, SetPlayerPosition,
+        SetPlayerPositionRotation, SetPlayerRotation, send_packet_from_thread,
+    },
+};
+
+pub const PLAYER_WIDTH: f64 = 0.6;
+pub const PLAYER_HEIGHT: f64 = 1.8;
+pub const SNEAKING_HEIGHT: f64 = 1.5;
+
+const STEP_HEIGHT: f64 = 0.6;
+const GRAVITY: f64 = 0.08;
+const JUMP_POWER: f64 = 0.42;
+/// Ticks between two jumps while the jump key is held
+const JUMP_DELAY: u8 = 10;
+const MOVEMENT_SPEED: f64 = 0.1;
+/// Velocities below this are set to 0 at the start of a tick
+const MIN_VELOCITY: f64 = 0.003;
+/// The position is sent at least once every second, even without moving
+const POSITION_REMINDER_TICKS: u32 = 20;
+
+/// Movement keys held by the player
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MovementInput {
+    /// Between -1 (backward) and 1 (forward)
+    pub forward: f32,
+    /// Between -1 (right) and 1 (left)
+    pub strafe: f32,
+    pub jump: bool,
+    pub sneak: bool,
+    pub sprint: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Physics {
+    pub input: MovementInput,
+    pub on_ground: bool,
+    pub horizontal_collision: bool,
+    /// Flying in creative mode, set by the server
+    pub flying: bool,
+    pub flying_speed: f32,
+    jump_delay: u8,
+    last_sent: Option<LastSent>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LastSent {
+    position: Vec3d,
+    rotation: Rotation,
+    on_ground: bool,
+    horizontal_collision: bool,
+    ticks: u32,
+}
+
+pub(crate) fn block_containing(pos: Vec3d) -> BlockPos {
+    BlockPos(Vec3i {
+        x: pos.x.floor() as i32,
+        y: pos.y.floor() as i32,
+        z: pos.z.floor() as i32,
+    })
+}
+
+impl BlockState {
+    /// Slipperiness of the block, higher values keep more speed
+    pub fn friction(self) -> f64 {
+        match self.name() {
+            "minecraft:ice" | "minecraft:packed_ice" | "minecraft:frosted_ice" => 0.98,
+            "minecraft:blue_ice" => 0.989,
+            "minecraft:slime_block" => 0.8,
+            _ => 0.6,
+        }
+    }
+}
+
+/// Collision boxes of the blocks overlapping `area`, in world coordinates
+fn collision_boxes(world: &World, area: Aabb) -> Vec<Aabb> {
+    area.blocks()
+        .flat_map(|pos| {
+            let offset = pos.0.into();
+            world
+                .collision_boxes(pos)
+                .iter()
+                .map(move |b| b.offset(offset))
+        })
+        .filter(|b| b.intersects(&area))
+        .collect()
+}
+
+fn no_collision(world: &World, bb: Aabb) -> bool {
+    collision_boxes(world, bb).is_empty()
+}
+
+/// Movement of `bb` stopped by `boxes`, vertically first like vanilla
+fn collide_with(boxes: &[Aabb], mut bb: Aabb, movement: Vec3d) -> Vec3d {
+    let clip = |axis: fn(&Aabb, &Aabb, f64) -> f64, bb: &Aabb, d: f64| {
+        boxes.iter().fold(d, |d, b| axis(b, bb, d))
+    };
+
+    let y = clip(Aabb::clip_y, &bb, movement.y);
+    bb = bb.offset(Vec3d { x: 0., y, z: 0. });
+
+    let z_first = movement.x.abs() < movement.z.abs();
+    let mut z = movement.z;
+    if z_first {
+        z = clip(Aabb::clip_z, &bb, z);
+        bb = bb.offset(Vec3d { x: 0., y: 0., z });
+    }
+    let x = clip(Aabb::clip_x, &bb, movement.x);
+    bb = bb.offset(Vec3d { x, y: 0., z: 0. });
+    if !z_first {
+        z = clip(Aabb::clip_z, &bb, z);
+    }
+    Vec3d { x, y, z }
+}
+
+fn collide(world: &World, bb: Aabb, movement: Vec3d) -> Vec3d {
+    let boxes = collision_boxes(world, bb.expand_towards(movement));
+    collide_with(&boxes, bb, movement)
+}
+
+fn horizontal_distance_sqr(v: Vec3d) -> f64 {
+    v.x * v.x + v.z * v.z
+}
+
+/// Movement of `bb` in the world, climbing blocks up to `STEP_HEIGHT` when on the ground
+fn collide_with_step(world: &World, bb: Aabb, movement: Vec3d, on_ground: bool) -> Vec3d {
+    let res = collide(world, bb, movement);
+
+    let falling_on_ground = res.y != movement.y && movement.y < 0.;
+    let horizontal_collision = res.x != movement.x || res.z != movement.z;
+    if !(on_ground || falling_on_ground) || !horizontal_collision {
+        return res;
+    }
+
+    let horizontal = Vec3d { y: 0., ..movement };
+    let mut stepped = collide(
+        world,
+        bb,
+        Vec3d {
+            y: STEP_HEIGHT,
+            ..movement
+        },
+    );
+    let up = collide(
+        world,
+        bb.expand_towards(horizontal),
+        Vec3d {
+            x: 0.,
+            y: STEP_HEIGHT,
+            z: 0.,
+        },
+    );
+    if up.y < STEP_HEIGHT {
+        let across = collide(world, bb.offset(up), horizontal) + up;
+        if horizontal_distance_sqr(across) > horizontal_distance_sqr(stepped) {
+            stepped = across;
+        }
+    }
+
+    if horizontal_distance_sqr(stepped) > horizontal_distance_sqr(res) {
+        let down = collide(
+            world,
+            bb.offset(stepped),
+            Vec3d {
+                x: 0.,
+                y: movement.y - stepped.y,
+                z: 0.,
+            },
+        );
+        return stepped + down;
+    }
+    res
+}
+
+/// Reduce the horizontal movement so that a sneaking player doesn't fall from an edge
+fn back_off_from_edge(world: &World, bb: Aabb, mut movement: Vec3d) -> Vec3d {
+    const STEP: f64 = 0.05;
+    fn approach(v: f64) -> f64 {
+        if v.abs() < STEP {
+            0.
+        } else {
+            v - STEP * v.signum()
+        }
+    }
+    let falls = |x: f64, z: f64| {
+        no_collision(
+            world,
+            bb.offset(Vec3d {
+                x,
+                y: -STEP_HEIGHT,
+                z,
+            }),
+        )
+    };
+
+    while movement.x != 0. && falls(movement.x, 0.) {
+        movement.x = approach(movement.x);
+    }
+    while movement.z != 0. && falls(0., movement.z) {
+        movement.z = approach(movement.z);
+    }
+    while movement.x != 0. && movement.z != 0. && falls(movement.x, movement.z) {
+        movement.x = approach(movement.x);
+        movement.z = approach(movement.z);
+    }
+    movement
+}
+
+/// Velocity given by the movement keys, rotated along the yaw of the player
+fn input_velocity(strafe: f64, forward: f64, speed: f64, yaw: f32) -> Vec3d {
+    let length_sqr = strafe * strafe + forward * forward;
+    if length_sqr < 1e-7 {
+        return Vec3d::default();
+    }
+    let scale = if length_sqr > 1. {
+        speed / length_sqr.sqrt()
+    } else {
+        speed
+    };
+    let (strafe, forward) = (strafe * scale, forward * scale);
+    let (sin, cos) = (yaw as f64).to_radians().sin_cos();
+    Vec3d {
+        x: strafe * cos - forward * sin,
+        y: 0.,
+        z: forward * cos + strafe * sin,
+    }
+}
+
+impl Game {
+    fn movement_speed(&self, sprinting: bool) -> f64 {
+        let level = |effect| {
+            self.player
+                .effect_amplifier(effect)
+                .map_or(0., |a| a as f64 + 1.)
+        };
+        let mut speed = MOVEMENT_SPEED * (1. + 0.2 * level(effects::SPEED));
+        speed *= (1. - 0.15 * level(effects::SLOWNESS)).max(0.);
+        if sprinting { speed * 1.3 } else { speed }
+    }
+
+    /// Move the player for one tick like the vanilla client, from `player.physics.input`
+    fn physics_step(&mut self) {
+        let input = self.player.physics.input;
+        let jump_boost = self
+            .player
+            .effect_amplifier(effects::JUMP_BOOST)
+            .map_or(0., |a| 0.1 * (a as f64 + 1.));
+
+        let spectator = self.player.game_mode == GameMode::Spectator;
+        let flying = self.player.physics.flying || spectator;
+
+        let sneaking = input.sneak && !flying;
+        let mut forward = input.forward.clamp(-1., 1.) as f64 * 0.98;
+        let mut strafe = input.strafe.clamp(-1., 1.) as f64 * 0.98;
+        if sneaking {
+            forward *= 0.3;
+            strafe *= 0.3;
+        }
+        let sprinting = input.sprint && forward > 0. && !sneaking;
+        let movement_speed = self.movement_speed(sprinting);
+
+        let mut entity = self.player.entity.write_arc();
+        let physics = &mut self.player.physics;
+
+        let mut velocity = entity.speed;
+        for v in [&mut velocity.x, &mut velocity.y, &mut velocity.z] {
+            if v.abs() < MIN_VELOCITY {
+                *v = 0.;
+            }
+        }
+
+        let pos = entity.position;
+        let in_water = self
+            .world
+            .block_at(block_containing(pos))
+            .is_some_and(BlockState::is_water);
+
+        physics.jump_delay = physics.jump_delay.saturating_sub(1);
+        if flying {
+            let vertical = physics.flying_speed as f64 * 3.;
+            if input.jump {
+                velocity.y += vertical;
+            }
+            if input.sneak {
+                velocity.y -= vertical;
+            }
+        } else if input.jump {
+            if in_water {
+                velocity.y += 0.04;
+            } else if physics.on_ground && physics.jump_delay == 0 {
+                velocity.y = JUMP_POWER + jump_boost;
+                if sprinting {
+                    let (sin, cos) = (entity.rotation.yaw as f64).to_radians().sin_cos();
+                    velocity.x -= sin * 0.2;
+                    velocity.z += cos * 0.2;
+                }
+                physics.jump_delay = JUMP_DELAY;
+            }
+        } else {
+            physics.jump_delay = 0;
+        }
+
+        let block_below = self.world.block_at(block_containing(Vec3d {
+            y: pos.y - 0.500001,
+            ..pos
+        }));
+        let block_friction = if physics.on_ground {
+            block_below.map_or(0.6, BlockState::friction)
+        } else {
+            1.
+        };
+        // The acceleration uses the block friction alone, the drag also includes the air
+        let drag = block_friction * 0.91;
+        let speed = if flying {
+            let speed = physics.flying_speed as f64;
+            if sprinting { speed * 2. } else { speed }
+        } else if in_water {
+            0.02
+        } else if physics.on_ground {
+            movement_speed * (0.21600002 / (block_friction * block_friction * block_friction))
+        } else if sprinting {
+            0.025999999
+        } else {
+            0.02
+        };
+        velocity += input_velocity(strafe, forward, speed, entity.rotation.yaw);
+
+        let height = if sneaking {
+            SNEAKING_HEIGHT
+        } else {
+            PLAYER_HEIGHT
+        };
+        let bb = Aabb::from_feet(pos, PLAYER_WIDTH, height);
+        let mut movement = velocity;
+        if sneaking && physics.on_ground && movement.y <= 0. {
+            movement = back_off_from_edge(&self.world, bb, movement);
+        }
+        let moved = if spectator {
+            movement
+        } else {
+            collide_with_step(&self.world, bb, movement, physics.on_ground)
+        };
+        entity.position = pos + moved;
+
+        let collided_x = moved.x != movement.x;
+        let collided_z = moved.z != movement.z;
+        let collided_y = moved.y != movement.y;
+        physics.horizontal_collision = collided_x || collided_z;
+        physics.on_ground = collided_y && movement.y < 0.;
+        if collided_x {
+            velocity.x = 0.;
+        }
+        if collided_z {
+            velocity.z = 0.;
+        }
+        if collided_y {
+            velocity.y = 0.;
+        }
+
+        if flying {
+            velocity.x *= 0.91;
+            velocity.z *= 0.91;
+            velocity.y *= 0.6;
+        } else if in_water {
+            velocity = velocity * 0.8;
+            velocity.y -= GRAVITY / 16.;
+        } else {
+            velocity.y = (velocity.y - GRAVITY) * 0.98;
+            velocity.x *= drag;
+            velocity.z *= drag;
+        }
+        entity.speed = velocity;
+    }
+
+    /// Move the player and send its position to the server
+    pub fn tick_physics(&mut self) -> Result<(), ReceiveError> {
+        // Like the vanilla client, the player doesn't move until its chunk is loaded
+        let pos = self.player.entity.read().position;
+        let chunk = ChunkPos::from_block_pos(block_containing(pos));
+        if !self.world.is_chunk_loaded(chunk) {
+            return Ok(());
+        }
+
+        self.physics_step();
+
+        let (position, rotation) = {
+            let entity = self.player.entity.read();
+            (entity.position, entity.rotation)
+        };
+        let physics = &mut self.player.physics;
+        let mut flags = PlayerPosFlags::empty();
+        flags.set(PlayerPosFlags::ON_GROUND, physics.on_ground);
+        flags.set(PlayerPosFlags::PUSHING_WALL, physics.horizontal_collision);
+
+        let (send_position, send_rotation, send_flags) = match &mut physics.last_sent {
+            Some(last) => {
+                last.ticks += 1;
+                (
+                    (position - last.position).length() > 2e-4
+                        || last.ticks >= POSITION_REMINDER_TICKS,
+                    last.rotation != rotation,
+                    last.on_ground != physics.on_ground
+                        || last.horizontal_collision != physics.horizontal_collision,
+                )
+            }
+            None => (true, true, true),
+        };
+
+        match (send_position, send_rotation) {
+            (true, true) => send_packet_from_thread(SetPlayerPositionRotation {
+                pos: position,
+                rotation,
+                flags,
+            })?,
+            (true, false) => send_packet_from_thread(SetPlayerPosition {
+                pos: position,
+                flags,
+            })?,
+            (false, true) => send_packet_from_thread(SetPlayerRotation { rotation, flags })?,
+            (false, false) if send_flags => {
+                send_packet_from_thread(SetPlayerMovementFlags { flags })?
+            }
+            (false, false) => return Ok(()),
+        }
+
+        let last = physics.last_sent.get_or_insert(LastSent {
+            position,
+            rotation,
+            on_ground: physics.on_ground,
+            horizontal_collision: physics.horizontal_collision,
+            ticks: 0,
+        });
+        if send_position {
+            last.position = position;
+            last.ticks = 0;
+        }
+        last.rotation = rotation;
+        last.on_ground = physics.on_ground;
+        last.horizontal_collision = physics.horizontal_collision;
+        Ok(())
+    }
+}
@@ -1,7 +1,48 @@
-use crate::game::entities::EntityRef;
+use std::collections::HashMap;
+
+use crate::game::{EntityId, GameMode, Physics, entities::EntityRef};
+
+/// IDs in the `minecraft:mob_effect` registry
+pub mod effects {
+    pub const SPEED: i32 = 0;
+    pub const SLOWNESS: i32 = 1;
+    pub const HASTE: i32 = 2;
+    pub const MINING_FATIGUE: i32 = 3;
+    pub const JUMP_BOOST: i32 = 7;
+    pub const CONDUIT_POWER: i32 = 28;
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct MobEffect {
+    /// 0 for level I
+    pub amplifier: i32,
+    /// In ticks, -1 for infinite
+    pub duration: i32,
+}
 
 #[derive(Debug, Default)]
 pub struct Player {
     pub name: String,
+    pub entity_id: EntityId,
     pub entity: EntityRef,
+    pub game_mode: GameMode,
+    /// Name of the dimension the player is in
+    pub dimension: String,
+    /// Active effects, by ID in the `minecraft:mob_effect` registry
+    pub effects: HashMap<i32, MobEffect>,
+    pub physics: Physics,
+    /// Sequence number of the last block interaction, acknowledged by the server
+    sequence: i32,
+}
+
+impl Player {
+    pub fn next_sequence(&mut self) -> i32 {
+        self.sequence += 1;
+        self.sequence
+    }
+
+    pub fn effect_amplifier(&self, effect: i32) -> Option<i32> {
+        self.effects.get(&effect).map(|e| e.amplifier)
+    }
 }
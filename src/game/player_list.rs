@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use log::warn;
+
+use crate::{
+    datatypes::TextComponent,
+    game::GameMode,
+    packets::{PlayerAction, PlayerProperty},
+};
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct PlayerInfo {
+    pub name: String,
+    pub properties: Vec<PlayerProperty>,
+    pub game_mode: GameMode,
+    /// Latency in milliseconds
+    pub latency: i32,
+    /// Shown in the tab list
+    pub listed: bool,
+    pub display_name: Option<TextComponent>,
+    pub list_priority: i32,
+    pub show_hat: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct PlayerList {
+    players: HashMap<u128, PlayerInfo>,
+    pub header: Option<TextComponent>,
+    pub footer: Option<TextComponent>,
+}
+
+impl PlayerList {
+    #[allow(dead_code)]
+    pub fn get(&self, uuid: u128) -> Option<&PlayerInfo> {
+        self.players.get(&uuid)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_by_name(&self, name: &str) -> Option<(u128, &PlayerInfo)> {
+        self.players
+            .iter()
+            .find(|(_, info)| info.name == name)
+            .map(|(uuid, info)| (*uuid, info))
+    }
+
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = (u128, &PlayerInfo)> {
+        self.players.iter().map(|(uuid, info)| (*uuid, info))
+    }
+
+    /// Players shown in the tab list, sorted like the vanilla client does
+    #[allow(dead_code)]
+    pub fn listed(&self) -> Vec<(u128, &PlayerInfo)> {
+        let mut players: Vec<_> = self.iter().filter(|(_, info)| info.listed).collect();
+        players.sort_by(|(_, a), (_, b)| {
+            b.list_priority
+                .cmp(&a.list_priority)
+                .then_with(|| {
+                    (a.game_mode == GameMode::Spectator).cmp(&(b.game_mode == GameMode::Spectator))
+                })
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+        players
+    }
+
+    pub fn update(&mut self, uuid: u128, actions: Vec<PlayerAction>) {
+        for action in actions {
+            if let PlayerAction::AddPlayer { name, properties } = action {
+                self.players.insert(
+                    uuid,
+                    PlayerInfo {
+                        name,
+                        properties,
+                        ..Default::default()
+                    },
+                );
+                continue;
+            }
+
+            let Some(info) = self.players.get_mut(&uuid) else {
+                warn!("Player info update for unknown player {:#x}", uuid);
+                return;
+            };
+
+            match action {
+                PlayerAction::AddPlayer { .. } => unreachable!(),
+                PlayerAction::InitializeChat(_) => (),
+                PlayerAction::UpdateGameMode(mode) => {
+                    info.game_mode = GameMode::from_id(mode.0).unwrap_or_default()
+                }
+                PlayerAction::UpdateListed(listed) => info.listed = listed,
+                PlayerAction::UpdateLatency(latency) => info.latency = latency.0,
+                PlayerAction::UpdateDisplayName(display_name) => info.display_name = display_name,
+                PlayerAction::UpdateListPriority(priority) => info.list_priority = priority.0,
+                PlayerAction::UpdateHat(show_hat) => info.show_hat = show_hat,
+            }
+        }
+    }
+
+    pub fn remove(&mut self, uuid: u128) {
+        self.players.remove(&uuid);
+    }
+}
@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+
+use crate::game::{IdSet, SlotDisplay, Tags, tags::ITEM_REGISTRY};
+
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct Recipes {
+    /// Item sets used by the client to know which items fit in some slots (e.g. furnace fuels)
+    pub property_sets: HashMap<String, Vec<i32>>,
+    /// Ingredient and result of the stonecutter recipes
+    pub stonecutter: Vec<(IdSet, SlotDisplay)>,
+}
+
+impl Recipes {
+    /// Results of the stonecutter recipes accepting `item_id` as ingredient
+    #[allow(dead_code)]
+    pub fn stonecutter_results<'a>(
+        &'a self,
+        tags: &'a Tags,
+        item_id: i32,
+    ) -> impl Iterator<Item = &'a SlotDisplay> {
+        self.stonecutter
+            .iter()
+            .filter(move |(ingredient, _)| tags.id_set_contains(ITEM_REGISTRY, ingredient, item_id))
+            .map(|(_, result)| result)
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use crate::{nbt::Nbt, packets::RegistryDataEntry};
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct RegistryEntry {
+    pub id: String,
+    pub data: Option<Nbt>,
+}
+
+impl From<RegistryDataEntry> for RegistryEntry {
+    fn from(RegistryDataEntry { entry_id, data }: RegistryDataEntry) -> Self {
+        Self { id: entry_id, data }
+    }
+}
+
+/// A registry sent by the server, the protocol ID of an entry is its index
+#[derive(Debug, Default)]
+pub struct Registry {
+    entries: Vec<RegistryEntry>,
+    ids: HashMap<String, i32>,
+}
+
+impl Registry {
+    #[allow(dead_code)]
+    pub fn get(&self, id: i32) -> Option<&RegistryEntry> {
+        usize::try_from(id).ok().and_then(|id| self.entries.get(id))
+    }
+
+    #[allow(dead_code)]
+    pub fn id_of(&self, identifier: &str) -> Option<i32> {
+        self.ids.get(identifier).copied()
+    }
+
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &RegistryEntry)> {
+        self.entries.iter().enumerate().map(|(i, e)| (i as i32, e))
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// A registry entry that can be decoded from its NBT
+pub trait RegistryType: Sized {
+    const REGISTRY: &'static str;
+
+    fn from_nbt(nbt: &Nbt) -> Option<Self>;
+}
+
+#[derive(Debug, Default)]
+pub struct Registries(HashMap<String, Registry>);
+
+impl Registries {
+    pub fn insert(&mut self, registry_id: String, entries: Vec<RegistryEntry>) {
+        let ids = entries
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (e.id.clone(), i as i32))
+            .collect();
+        self.0.insert(registry_id, Registry { entries, ids });
+    }
+
+    pub fn get(&self, registry_id: &str) -> Option<&Registry> {
+        self.0.get(registry_id)
+    }
+
+    /// Decode the entry `id` of the registry of `T`
+    pub fn get_typed<T: RegistryType>(&self, id: i32) -> Option<T> {
+        let entry = self.get(T::REGISTRY)?.get(id)?;
+        T::from_nbt(entry.data.as_ref()?)
+    }
+
+    #[allow(dead_code)]
+    pub fn dimension_type(&self, id: i32) -> Option<DimensionType> {
+        self.get_typed(id)
+    }
+
+    #[allow(dead_code)]
+    pub fn biome(&self, id: i32) -> Option<Biome> {
+        self.get_typed(id)
+    }
+
+    #[allow(dead_code)]
+    pub fn chat_type(&self, id: i32) -> Option<ChatType> {
+        self.get_typed(id)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct DimensionType {
+    pub min_y: i32,
+    pub height: i32,
+    /// Max height to which portals can bring players
+    pub logical_height: i32,
+    pub has_skylight: bool,
+    pub has_ceiling: bool,
+    pub ultrawarm: bool,
+    pub coordinate_scale: f64,
+}
+
+impl RegistryType for DimensionType {
+    const REGISTRY: &'static str = "minecraft:dimension_type";
+
+    fn from_nbt(nbt: &Nbt) -> Option<Self> {
+        Some(Self {
+            min_y: nbt.get("min_y")?.as_int()? as i32,
+            height: nbt.get("height")?.as_int()? as i32,
+            logical_height: nbt.get("logical_height")?.as_int()? as i32,
+            has_skylight: nbt.get("has_skylight")?.as_bool()?,
+            has_ceiling: nbt.get("has_ceiling")?.as_bool()?,
+            ultrawarm: nbt.get("ultrawarm")?.as_bool()?,
+            coordinate_scale: nbt.get("coordinate_scale")?.as_float()?,
+        })
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct Biome {
+    pub has_precipitation: bool,
+    pub temperature: f32,
+    pub downfall: f32,
+    pub temperature_modifier: Option<String>,
+}
+
+impl RegistryType for Biome {
+    const REGISTRY: &'static str = "minecraft:worldgen/biome";
+
+    fn from_nbt(nbt: &Nbt) -> Option<Self> {
+        Some(Self {
+            has_precipitation: nbt.get("has_precipitation")?.as_bool()?,
+            temperature: nbt.get("temperature")?.as_float()? as f32,
+            downfall: nbt.get("downfall")?.as_float()? as f32,
+            temperature_modifier: nbt
+                .get("temperature_modifier")
+                .and_then(Nbt::as_str)
+                .map(str::to_string),
+        })
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct ChatType {
+    pub chat: ChatTypeDecoration,
+    pub narration: ChatTypeDecoration,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatTypeDecoration {
+    pub translation_key: String,
+    /// Among `sender`, `target` and `content`
+    pub parameters: Vec<String>,
+}
+
+impl ChatTypeDecoration {
+    fn from_nbt(nbt: &Nbt) -> Option<Self> {
+        Some(Self {
+            translation_key: nbt.get("translation_key")?.as_str()?.to_string(),
+            parameters: nbt
+                .get("parameters")?
+                .as_list()?
+                .iter()
+                .map(|p| p.as_str().map(str::to_string))
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+impl RegistryType for ChatType {
+    const REGISTRY: &'static str = "minecraft:chat_type";
+
+    fn from_nbt(nbt: &Nbt) -> Option<Self> {
+        Some(Self {
+            chat: ChatTypeDecoration::from_nbt(nbt.get("chat")?)?,
+            narration: ChatTypeDecoration::from_nbt(nbt.get("narration")?)?,
+        })
+    }
+}
+
+impl ChatType {
+    /// Format a message the way the vanilla client displays it (english only)
+    #[allow(dead_code)]
+    pub fn format(&self, sender: &str, content: &str, target: Option<&str>) -> String {
+        let pattern = match self.chat.translation_key.as_str() {
+            "chat.type.text" => "<%s> %s",
+            "chat.type.emote" => "* %s %s",
+            "chat.type.announcement" => "[%s] %s",
+            "chat.type.admin" => "[%s: %s]",
+            "chat.type.team.text" => "%s <%s> %s",
+            "chat.type.team.sent" => "-> %s <%s> %s",
+            "commands.message.display.incoming" => "%s whispers to you: %s",
+            "commands.message.display.outgoing" => "You whisper to %s: %s",
+            _ => return format!("<{}> {}", sender, content),
+        };
+
+        let mut args = self.chat.parameters.iter().map(|p| match p.as_str() {
+            "sender" => sender,
+            "target" => target.unwrap_or_default(),
+            _ => content,
+        });
+
+        let mut out = String::new();
+        for (i, part) in pattern.split("%s").enumerate() {
+            if i > 0 {
+                out.push_str(args.next().unwrap_or_default());
+            }
+            out.push_str(part);
+        }
+        out
+    }
+}
@@ -0,0 +1,281 @@
+use std::sync::OnceLock;
+
+use crate::game::{Aabb, BlockState, STATES};
+
+/// Box in pixels, 16 being the size of a block
+const fn px(min_x: f64, min_y: f64, min_z: f64, max_x: f64, max_y: f64, max_z: f64) -> Aabb {
+    Aabb::new(
+        min_x / 16.,
+        min_y / 16.,
+        min_z / 16.,
+        max_x / 16.,
+        max_y / 16.,
+        max_z / 16.,
+    )
+}
+
+fn clockwise(direction: &str) -> &'static str {
+    match direction {
+        "north" => "east",
+        "east" => "south",
+        "south" => "west",
+        _ => "north",
+    }
+}
+
+fn opposite(direction: &str) -> &'static str {
+    clockwise(clockwise(direction))
+}
+
+fn counter_clockwise(direction: &str) -> &'static str {
+    clockwise(opposite(direction))
+}
+
+/// Slice of the block against its `side`, `depth` pixels thick
+fn side_box(side: &str, depth: f64, min_y: f64, max_y: f64) -> Aabb {
+    match side {
+        "north" => px(0., min_y, 0., 16., max_y, depth),
+        "south" => px(0., min_y, 16. - depth, 16., max_y, 16.),
+        "west" => px(0., min_y, 0., depth, max_y, 16.),
+        _ => px(16. - depth, min_y, 0., 16., max_y, 16.),
+    }
+}
+
+fn intersection(a: Aabb, b: Aabb) -> Aabb {
+    Aabb::new(
+        a.min.x.max(b.min.x),
+        a.min.y.max(b.min.y),
+        a.min.z.max(b.min.z),
+        a.max.x.min(b.max.x),
+        a.max.y.min(b.max.y),
+        a.max.z.min(b.max.z),
+    )
+}
+
+fn stairs(state: BlockState) -> Vec<Aabb> {
+    let facing = state.property("facing").unwrap_or("north");
+    let (slab, step) = match state.property("half") {
+        Some("top") => ((8., 16.), (0., 8.)),
+        _ => ((0., 8.), (8., 16.)),
+    };
+    let half = |side| side_box(side, 8., step.0, step.1);
+    let quarter = |a, b| intersection(half(a), half(b));
+
+    let mut boxes = vec![px(0., slab.0, 0., 16., slab.1, 16.)];
+    match state.property("shape") {
+        Some("outer_left") => boxes.push(quarter(facing, counter_clockwise(facing))),
+        Some("outer_right") => boxes.push(quarter(facing, clockwise(facing))),
+        Some("inner_left") => {
+            boxes.push(half(facing));
+            boxes.push(quarter(opposite(facing), counter_clockwise(facing)));
+        }
+        Some("inner_right") => {
+            boxes.push(half(facing));
+            boxes.push(quarter(opposite(facing), clockwise(facing)));
+        }
+        _ => boxes.push(half(facing)),
+    }
+    boxes
+}
+
+/// Post with an arm towards each connected side, like fences, walls and panes
+fn post_with_arms(
+    state: BlockState,
+    post: Option<(f64, f64)>,
+    arm_width: f64,
+    height: f64,
+    arm_length: f64,
+) -> Vec<Aabb> {
+    let (a, b) = (8. - arm_width, 8. + arm_width);
+    let mut boxes: Vec<Aabb> = post
+        .map(|(min, max)| px(min, 0., min, max, height, max))
+        .into_iter()
+        .collect();
+    for side in ["north", "east", "south", "west"] {
+        if matches!(state.property(side), None | Some("false" | "none")) {
+            continue;
+        }
+        boxes.push(match side {
+            "north" => px(a, 0., 0., b, height, arm_length),
+            "south" => px(a, 0., 16. - arm_length, b, height, 16.),
+            "west" => px(0., 0., a, arm_length, height, b),
+            _ => px(16. - arm_length, 0., a, 16., height, b),
+        });
+    }
+    boxes
+}
+
+fn door(state: BlockState) -> Vec<Aabb> {
+    let facing = state.property("facing").unwrap_or("north");
+    // Closed doors are against the side the player placed them from
+    let side = match (state.property("open"), state.property("hinge")) {
+        (Some("true"), Some("right")) => opposite(counter_clockwise(facing)),
+        (Some("true"), _) => opposite(clockwise(facing)),
+        _ => opposite(facing),
+    };
+    vec![side_box(side, 3., 0., 16.)]
+}
+
+fn chest(state: BlockState) -> Vec<Aabb> {
+    let facing = state.property("facing").unwrap_or("north");
+    let connected = match state.property("type") {
+        Some("left") => Some(clockwise(facing)),
+        Some("right") => Some(counter_clockwise(facing)),
+        _ => None,
+    };
+    let mut chest = px(1., 0., 1., 15., 14., 15.);
+    match connected {
+        Some("north") => chest.min.z = 0.,
+        Some("south") => chest.max.z = 1.,
+        Some("west") => chest.min.x = 0.,
+        Some("east") => chest.max.x = 1.,
+        _ => (),
+    }
+    vec![chest]
+}
+
+/// Hollow blocks like cauldrons and composters, approximated with a floor and four walls
+fn basin(floor: f64) -> Vec<Aabb> {
+    vec![
+        px(0., 0., 0., 16., floor, 16.),
+        px(0., 0., 0., 2., 16., 16.),
+        px(14., 0., 0., 16., 16., 16.),
+        px(2., 0., 0., 14., 16., 2.),
+        px(2., 0., 14., 14., 16., 16.),
+    ]
+}
+
+/// Collision boxes of the blocks whose shape isn't a full cube, relative to the block.
+///
+/// Vanilla doesn't export block shapes, this table covers the blocks a player commonly walks on
+/// or against. The other solid blocks are full cubes.
+fn shape_of(state: BlockState) -> Vec<Aabb> {
+    if !state.is_solid() {
+        return Vec::new();
+    }
+    let Some(block) = state.block() else {
+        return vec![Aabb::FULL_BLOCK];
+    };
+    let prop = |name| state.property(name).unwrap_or_default();
+    let facing = prop("facing");
+
+    match block.block_type {
+        "minecraft:slab" => match prop("type") {
+            "top" => vec![px(0., 8., 0., 16., 16., 16.)],
+            "bottom" => vec![px(0., 0., 0., 16., 8., 16.)],
+            _ => vec![Aabb::FULL_BLOCK],
+        },
+        "minecraft:stair" => stairs(state),
+        "minecraft:fence" => post_with_arms(state, Some((6., 10.)), 2., 24., 8.),
+        "minecraft:wall" => {
+            let post = (prop("up") == "true").then_some((4., 12.));
+            post_with_arms(state, post, 3., 24., 11.)
+        }
+        "minecraft:iron_bars" | "minecraft:stained_glass_pane" => {
+            post_with_arms(state, Some((7., 9.)), 1., 16., 8.)
+        }
+        "minecraft:fence_gate" => match (prop("open"), facing) {
+            ("true", _) => Vec::new(),
+            (_, "north" | "south") => vec![px(0., 0., 6., 16., 24., 10.)],
+            _ => vec![px(6., 0., 0., 10., 24., 16.)],
+        },
+        "minecraft:door" => door(state),
+        "minecraft:trapdoor" => match (prop("open"), prop("half")) {
+            ("true", _) => vec![side_box(opposite(facing), 3., 0., 16.)],
+            (_, "top") => vec![px(0., 13., 0., 16., 16., 16.)],
+            _ => vec![px(0., 0., 0., 16., 3., 16.)],
+        },
+        "minecraft:ladder" => vec![side_box(opposite(facing), 3., 0., 16.)],
+        "minecraft:wool_carpet" | "minecraft:carpet" | "minecraft:mossy_carpet" => {
+            vec![px(0., 0., 0., 16., 1., 16.)]
+        }
+        "minecraft:snow_layer" => {
+            let layers: f64 = prop("layers").parse().unwrap_or(1.);
+            if layers > 1. {
+                vec![px(0., 0., 0., 16., (layers - 1.) * 2., 16.)]
+            } else {
+                Vec::new()
+            }
+        }
+        "minecraft:powder_snow" | "minecraft:scaffolding" => Vec::new(),
+        "minecraft:bed" => vec![px(0., 0., 0., 16., 9., 16.)],
+        "minecraft:chest" | "minecraft:trapped_chest" => chest(state),
+        "minecraft:ender_chest" => vec![px(1., 0., 1., 15., 14., 15.)],
+        "minecraft:farm" | "minecraft:dirt_path" => vec![px(0., 0., 0., 16., 15., 16.)],
+        "minecraft:soul_sand" | "minecraft:mud" => vec![px(0., 0., 0., 16., 14., 16.)],
+        "minecraft:cactus" | "minecraft:honey" => vec![px(1., 0., 1., 15., 15., 15.)],
+        "minecraft:enchantment_table" => vec![px(0., 0., 0., 16., 12., 16.)],
+        "minecraft:daylight_detector" => vec![px(0., 0., 0., 16., 6., 16.)],
+        "minecraft:campfire" => vec![px(0., 0., 0., 16., 7., 16.)],
+        "minecraft:stonecutter" => vec![px(0., 0., 0., 16., 9., 16.)],
+        "minecraft:waterlily" => vec![px(1., 0., 1., 15., 1.5, 15.)],
+        "minecraft:flower_pot" => vec![px(5., 0., 5., 11., 6., 11.)],
+        "minecraft:sea_pickle" | "minecraft:candle" => vec![px(6., 0., 6., 10., 6., 10.)],
+        "minecraft:dragon_egg" => vec![px(1., 0., 1., 15., 16., 15.)],
+        "minecraft:conduit" => vec![px(5., 5., 5., 11., 11., 11.)],
+        "minecraft:bamboo_stalk" => vec![px(6.5, 0., 6.5, 9.5, 16., 9.5)],
+        "minecraft:cake" => {
+            let bites: f64 = prop("bites").parse().unwrap_or(0.);
+            vec![px(1. + bites * 2., 0., 1., 15., 8., 15.)]
+        }
+        "minecraft:lantern" => match prop("hanging") {
+            "true" => vec![px(5., 1., 5., 11., 8., 11.), px(6., 8., 6., 10., 10., 10.)],
+            _ => vec![px(5., 0., 5., 11., 7., 11.), px(6., 7., 6., 10., 9., 10.)],
+        },
+        "minecraft:chain" => match prop("axis") {
+            "x" => vec![px(0., 6.5, 6.5, 16., 9.5, 9.5)],
+            "z" => vec![px(6.5, 6.5, 0., 9.5, 9.5, 16.)],
+            _ => vec![px(6.5, 0., 6.5, 9.5, 16., 9.5)],
+        },
+        "minecraft:end_portal_frame" => {
+            let mut boxes = vec![px(0., 0., 0., 16., 13., 16.)];
+            if prop("eye") == "true" {
+                boxes.push(px(4., 13., 4., 12., 16., 12.));
+            }
+            boxes
+        }
+        "minecraft:brewing_stand" => {
+            vec![px(0., 0., 0., 16., 2., 16.), px(7., 2., 7., 9., 14., 9.)]
+        }
+        "minecraft:lectern" => vec![px(0., 0., 0., 16., 2., 16.), px(4., 2., 4., 12., 14., 12.)],
+        "minecraft:anvil" => {
+            let top = match facing {
+                "north" | "south" => px(3., 10., 0., 13., 16., 16.),
+                _ => px(0., 10., 3., 16., 16., 13.),
+            };
+            vec![
+                px(2., 0., 2., 14., 4., 14.),
+                px(4., 4., 4., 12., 10., 12.),
+                top,
+            ]
+        }
+        "minecraft:hopper" => vec![
+            px(0., 10., 0., 16., 16., 16.),
+            px(4., 4., 4., 12., 10., 12.),
+        ],
+        "minecraft:cauldron" | "minecraft:layered_cauldron" | "minecraft:lava_cauldron" => {
+            basin(4.)
+        }
+        "minecraft:composter" => basin(2.),
+        _ => vec![Aabb::FULL_BLOCK],
+    }
+}
+
+impl BlockState {
+    /// Collision boxes of this block, relative to the block
+    pub fn collision_boxes(self) -> &'static [Aabb] {
+        static SHAPES: OnceLock<Vec<Vec<Aabb>>> = OnceLock::new();
+
+        let shapes = SHAPES.get_or_init(|| {
+            (0..STATES.len() as i32)
+                .map(|id| shape_of(BlockState(id)))
+                .collect()
+        });
+        match usize::try_from(self.0).ok().and_then(|id| shapes.get(id)) {
+            Some(shape) => shape,
+            // Without the table, every block but the air is a full cube
+            None if self.is_solid() => &[Aabb::FULL_BLOCK],
+            None => &[],
+        }
+    }
+}
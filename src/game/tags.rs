@@ -0,0 +1,78 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::game::{BlockState, IdSet};
+
+pub const BLOCK_REGISTRY: &str = "minecraft:block";
+pub const ITEM_REGISTRY: &str = "minecraft:item";
+
+/// Tags of every registry, tag names are stored without the leading `#`
+#[derive(Debug, Default)]
+pub struct Tags(HashMap<String, HashMap<String, HashSet<i32>>>);
+
+/// Accept `#minecraft:logs`, `minecraft:logs` and `logs`
+fn normalize(tag: &str) -> String {
+    let tag = tag.strip_prefix('#').unwrap_or(tag);
+    if tag.contains(':') {
+        tag.to_string()
+    } else {
+        format!("minecraft:{}", tag)
+    }
+}
+
+impl Tags {
+    /// Replace the tags of a registry
+    pub fn set(&mut self, registry: String, tags: HashMap<String, HashSet<i32>>) {
+        self.0.insert(registry, tags);
+    }
+
+    pub fn get(&self, registry: &str, tag: &str) -> Option<&HashSet<i32>> {
+        self.0.get(registry)?.get(&normalize(tag))
+    }
+
+    /// Tags of `registry` containing `id`
+    #[allow(dead_code)]
+    pub fn tags_of(&self, registry: &str, id: i32) -> impl Iterator<Item = &str> {
+        self.0
+            .get(registry)
+            .into_iter()
+            .flatten()
+            .filter(move |(_, ids)| ids.contains(&id))
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn has(&self, registry: &str, tag: &str, id: i32) -> bool {
+        self.get(registry, tag).is_some_and(|ids| ids.contains(&id))
+    }
+
+    #[allow(dead_code)]
+    pub fn block_has(&self, tag: &str, state: BlockState) -> bool {
+        state
+            .block_id()
+            .is_some_and(|block_id| self.has(BLOCK_REGISTRY, tag, block_id))
+    }
+
+    #[allow(dead_code)]
+    pub fn item_has(&self, tag: &str, item_id: i32) -> bool {
+        self.has(ITEM_REGISTRY, tag, item_id)
+    }
+
+    /// IDs of an ID set, resolving tags from `registry`
+    #[allow(dead_code)]
+    pub fn resolve(&self, registry: &str, set: &IdSet) -> Vec<i32> {
+        match set {
+            IdSet::TagName(tag) => self
+                .get(registry, tag)
+                .map(|ids| ids.iter().copied().collect())
+                .unwrap_or_default(),
+            IdSet::Ids(ids) => ids.iter().map(|id| id.0).collect(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn id_set_contains(&self, registry: &str, set: &IdSet, id: i32) -> bool {
+        match set {
+            IdSet::TagName(tag) => self.has(registry, tag, id),
+            IdSet::Ids(ids) => ids.iter().any(|v| v.0 == id),
+        }
+    }
+}
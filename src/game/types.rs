@@ -5,7 +5,10 @@ use std::{
 
 use macros::{Deserialize, Serialize};
 
-use crate::datatypes::{Angle, BlockPos, VarInt};
+use crate::{
+    datatypes::{Angle, BlockPos, TextComponent, VarInt},
+    game::{Enchantment, Item, StructuredComponent, Tool},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 pub struct Vec2<T> {
@@ -158,11 +161,12 @@ impl_ops!(Vec2, {x, z});
 impl_ops!(Vec3, {x, y, z});
 
 impl Vec3d {
+    /// Speed in blocks per tick, the protocol sends it in 1/8000 of a block per tick
     pub fn speed_from_entity_velocity(vx: i16, vy: i16, vz: i16) -> Self {
         Self {
-            x: vx as f64 / 400.,
-            y: vy as f64 / 400.,
-            z: vz as f64 / 400.,
+            x: vx as f64 / 8000.,
+            y: vy as f64 / 8000.,
+            z: vz as f64 / 8000.,
         }
     }
 }
@@ -180,10 +184,211 @@ impl Rotation {
             pitch: pitch.into(),
         }
     }
+
+    /// Rotation of an entity at `from` looking at `to`
+    pub fn looking_at(from: Vec3d, to: Vec3d) -> Self {
+        let diff = to - from;
+        let mut yaw = -f64::atan2(diff.x, diff.z).to_degrees() as f32;
+        if yaw < 0. {
+            yaw += 360.;
+        }
+        let horizontal = Vec3d {
+            x: diff.x,
+            y: 0.,
+            z: diff.z,
+        }
+        .length();
+        let pitch = -f64::atan2(diff.y, horizontal).to_degrees() as f32;
+        Self { yaw, pitch }
+    }
+}
+
+/// Axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Aabb {
+    pub min: Vec3d,
+    pub max: Vec3d,
+}
+
+impl Aabb {
+    pub const fn new(
+        min_x: f64,
+        min_y: f64,
+        min_z: f64,
+        max_x: f64,
+        max_y: f64,
+        max_z: f64,
+    ) -> Self {
+        Self {
+            min: Vec3 {
+                x: min_x,
+                y: min_y,
+                z: min_z,
+            },
+            max: Vec3 {
+                x: max_x,
+                y: max_y,
+                z: max_z,
+            },
+        }
+    }
+
+    pub const FULL_BLOCK: Self = Self::new(0., 0., 0., 1., 1., 1.);
+
+    /// Box of an entity standing at `pos`
+    pub fn from_feet(pos: Vec3d, width: f64, height: f64) -> Self {
+        let half = width / 2.;
+        Self::new(
+            pos.x - half,
+            pos.y,
+            pos.z - half,
+            pos.x + half,
+            pos.y + height,
+            pos.z + half,
+        )
+    }
+
+    pub fn offset(self, by: Vec3d) -> Self {
+        Self {
+            min: self.min + by,
+            max: self.max + by,
+        }
+    }
+
+    /// Box covering the whole path of this box moved by `movement`
+    pub fn expand_towards(self, movement: Vec3d) -> Self {
+        let mut res = self;
+        macro_rules! expand_axis {
+            ($axis: ident) => {
+                if movement.$axis < 0. {
+                    res.min.$axis += movement.$axis;
+                } else {
+                    res.max.$axis += movement.$axis;
+                }
+            };
+        }
+        expand_axis!(x);
+        expand_axis!(y);
+        expand_axis!(z);
+        res
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x < other.max.x
+            && self.max.x > other.min.x
+            && self.min.y < other.max.y
+            && self.max.y > other.min.y
+            && self.min.z < other.max.z
+            && self.max.z > other.min.z
+    }
+
+    /// Positions of the blocks this box overlaps
+    pub fn blocks(&self) -> impl Iterator<Item = BlockPos> + use<> {
+        // Shapes like fences stick out of their block, one more block is checked below
+        let min = Vec3i {
+            x: self.min.x.floor() as i32,
+            y: self.min.y.floor() as i32 - 1,
+            z: self.min.z.floor() as i32,
+        };
+        let max = Vec3i {
+            x: self.max.x.ceil() as i32,
+            y: self.max.y.ceil() as i32,
+            z: self.max.z.ceil() as i32,
+        };
+        (min.x..max.x).flat_map(move |x| {
+            (min.y..max.y)
+                .flat_map(move |y| (min.z..max.z).map(move |z| BlockPos(Vec3i { x, y, z })))
+        })
+    }
+}
+
+/// Clamp the movement of `moving` along one axis so it stops at `obstacle`
+macro_rules! impl_clip {
+    ($name: ident, $axis: ident, $o1: ident, $o2: ident) => {
+        impl Aabb {
+            pub fn $name(&self, moving: &Self, mut movement: f64) -> f64 {
+                if moving.max.$o1 <= self.min.$o1
+                    || moving.min.$o1 >= self.max.$o1
+                    || moving.max.$o2 <= self.min.$o2
+                    || moving.min.$o2 >= self.max.$o2
+                {
+                    return movement;
+                }
+                if movement > 0. && moving.max.$axis <= self.min.$axis {
+                    movement = movement.min(self.min.$axis - moving.max.$axis);
+                } else if movement < 0. && moving.min.$axis >= self.max.$axis {
+                    movement = movement.max(self.max.$axis - moving.min.$axis);
+                }
+                movement
+            }
+        }
+    };
+}
+
+impl_clip!(clip_x, x, y, z);
+impl_clip!(clip_y, y, x, z);
+impl_clip!(clip_z, z, x, y);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameMode {
+    #[default]
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+impl GameMode {
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            0 => Some(Self::Survival),
+            1 => Some(Self::Creative),
+            2 => Some(Self::Adventure),
+            3 => Some(Self::Spectator),
+            _ => None,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[enum_repr(VarInt)]
+pub enum Hand {
+    #[default]
+    Main,
+    Off,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[enum_repr(VarInt)]
+pub enum BlockFace {
+    Bottom,
+    Top,
+    North,
+    South,
+    West,
+    East,
+}
+
+impl BlockFace {
+    /// Direction the face points to
+    #[allow(dead_code)]
+    pub fn normal(self) -> Vec3i {
+        let (x, y, z) = match self {
+            Self::Bottom => (0, -1, 0),
+            Self::Top => (0, 1, 0),
+            Self::North => (0, 0, -1),
+            Self::South => (0, 0, 1),
+            Self::West => (-1, 0, 0),
+            Self::East => (1, 0, 0),
+        };
+        Vec3i { x, y, z }
+    }
 }
 
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum IdSet {
     TagName(String),
     Ids(Vec<VarInt>),
@@ -221,18 +426,184 @@ pub enum SlotDisplay {
 }
 
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub enum Slot {
+    #[default]
     Empty,
     NonEmpty {
         count: VarInt,
         id: VarInt,
         components_to_add: Vec<StructuredComponent>,
+        /// IDs of the removed default components
         components_to_remove: Vec<VarInt>,
     },
 }
 
-#[derive(Debug)]
-pub enum StructuredComponent {
-    // TODO
+/// IDs in the `minecraft:data_component_type` registry of the components with a default value
+const MAX_STACK_SIZE_COMPONENT: i32 = 1;
+const MAX_DAMAGE_COMPONENT: i32 = 2;
+
+#[allow(dead_code)]
+impl Slot {
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    pub fn item(&self) -> Option<Item> {
+        match self {
+            Self::Empty => None,
+            Self::NonEmpty { id, .. } => Some(Item(id.0)),
+        }
+    }
+
+    pub fn count(&self) -> i32 {
+        match self {
+            Self::Empty => 0,
+            Self::NonEmpty { count, .. } => count.0,
+        }
+    }
+
+    /// Same stack with another count, empty if `count` is not positive
+    pub fn with_count(&self, count: i32) -> Self {
+        match self {
+            Self::NonEmpty {
+                id,
+                components_to_add,
+                components_to_remove,
+                ..
+            } if count > 0 => Self::NonEmpty {
+                count: VarInt(count),
+                id: *id,
+                components_to_add: components_to_add.clone(),
+                components_to_remove: components_to_remove.clone(),
+            },
+            _ => Self::Empty,
+        }
+    }
+
+    /// Both stacks can be merged: same item with the same components
+    pub fn is_same_item(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::NonEmpty {
+                    id,
+                    components_to_add,
+                    components_to_remove,
+                    ..
+                },
+                Self::NonEmpty {
+                    id: other_id,
+                    components_to_add: other_components_to_add,
+                    components_to_remove: other_components_to_remove,
+                    ..
+                },
+            ) => {
+                id == other_id
+                    && components_to_add == other_components_to_add
+                    && components_to_remove == other_components_to_remove
+            }
+            _ => false,
+        }
+    }
+
+    pub fn components(&self) -> &[StructuredComponent] {
+        match self {
+            Self::Empty => &[],
+            Self::NonEmpty {
+                components_to_add, ..
+            } => components_to_add,
+        }
+    }
+
+    fn is_removed(&self, component_id: i32) -> bool {
+        match self {
+            Self::Empty => false,
+            Self::NonEmpty {
+                components_to_remove,
+                ..
+            } => components_to_remove.iter().any(|c| c.0 == component_id),
+        }
+    }
+
+    pub fn max_stack_size(&self) -> i32 {
+        let Some(item) = self.item() else {
+            return 0;
+        };
+        self.components()
+            .iter()
+            .find_map(|c| match c {
+                StructuredComponent::MaxStackSize(size) => Some(size.0),
+                _ => None,
+            })
+            .unwrap_or_else(|| match self.is_removed(MAX_STACK_SIZE_COMPONENT) {
+                true => 1,
+                false => item.max_stack_size(),
+            })
+    }
+
+    /// Durability of the item, `None` if it can't be damaged
+    pub fn max_damage(&self) -> Option<i32> {
+        let item = self.item()?;
+        let unbreakable = self
+            .components()
+            .iter()
+            .any(|c| matches!(c, StructuredComponent::Unbreakable));
+        if unbreakable || self.is_removed(MAX_DAMAGE_COMPONENT) {
+            return None;
+        }
+        self.components()
+            .iter()
+            .find_map(|c| match c {
+                StructuredComponent::MaxDamage(max) => Some(max.0),
+                _ => None,
+            })
+            .or(Some(item.max_damage()))
+            .filter(|&max| max > 0)
+    }
+
+    pub fn damage(&self) -> i32 {
+        self.components()
+            .iter()
+            .find_map(|c| match c {
+                StructuredComponent::Damage(damage) => Some(damage.0),
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    /// Name given with an anvil
+    pub fn custom_name(&self) -> Option<&TextComponent> {
+        self.components().iter().find_map(|c| match c {
+            StructuredComponent::CustomName(name) => Some(name),
+            _ => None,
+        })
+    }
+
+    /// Displayed name of the item, without styling
+    pub fn display_name(&self) -> String {
+        let name = self.components().iter().find_map(|c| match c {
+            StructuredComponent::CustomName(name) | StructuredComponent::ItemName(name) => {
+                Some(name.to_plain())
+            }
+            _ => None,
+        });
+        name.unwrap_or_else(|| self.item().unwrap_or(Item::AIR).to_string())
+    }
+
+    pub fn enchantments(&self) -> &[Enchantment] {
+        self.components()
+            .iter()
+            .find_map(|c| match c {
+                StructuredComponent::Enchantments(enchantments) => Some(enchantments.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn tool(&self) -> Option<&Tool> {
+        self.components().iter().find_map(|c| match c {
+            StructuredComponent::Tool(tool) => Some(tool),
+            _ => None,
+        })
+    }
 }
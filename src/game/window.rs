@@ -0,0 +1,261 @@
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+use log::warn;
+
+use crate::{
+    datatypes::{BlockPos, TextComponent, VarInt},
+    game::{BlockFace, Game, Hand, PLAYER_WINDOW_ID, Window},
+    packets::{CloseContainer, ReceiveError, send_packet_from_thread},
+};
+
+/// Type of a screen, from the `minecraft:menu` registry
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuType {
+    /// Chests and barrels, with the number of rows
+    Generic9x(u8),
+    /// Dispensers and droppers
+    Generic3x3,
+    Crafter3x3,
+    Anvil,
+    Beacon,
+    BlastFurnace,
+    BrewingStand,
+    Crafting,
+    Enchantment,
+    Furnace,
+    Grindstone,
+    Hopper,
+    Lectern,
+    Loom,
+    Merchant,
+    ShulkerBox,
+    Smithing,
+    Smoker,
+    CartographyTable,
+    Stonecutter,
+}
+
+impl MenuType {
+    pub fn from_id(id: i32) -> Option<Self> {
+        Some(match id {
+            0..=5 => Self::Generic9x(id as u8 + 1),
+            6 => Self::Generic3x3,
+            7 => Self::Crafter3x3,
+            8 => Self::Anvil,
+            9 => Self::Beacon,
+            10 => Self::BlastFurnace,
+            11 => Self::BrewingStand,
+            12 => Self::Crafting,
+            13 => Self::Enchantment,
+            14 => Self::Furnace,
+            15 => Self::Grindstone,
+            16 => Self::Hopper,
+            17 => Self::Lectern,
+            18 => Self::Loom,
+            19 => Self::Merchant,
+            20 => Self::ShulkerBox,
+            21 => Self::Smithing,
+            22 => Self::Smoker,
+            23 => Self::CartographyTable,
+            24 => Self::Stonecutter,
+            _ => return None,
+        })
+    }
+}
+
+/// Properties of a screen, sent with SetContainerProperty
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScreenProperties {
+    Furnace {
+        /// Remaining burn time of the fuel, in ticks
+        fuel_left: i16,
+        /// Burn time of the last fuel
+        max_fuel: i16,
+        progress: i16,
+        max_progress: i16,
+    },
+    Enchantment {
+        /// Experience levels required by each option
+        level_requirements: [i16; 3],
+        seed: i16,
+        /// ID of the enchantment shown for each option, -1 if none
+        enchantments: [i16; 3],
+        levels: [i16; 3],
+    },
+    Beacon {
+        power_level: i16,
+        first_effect: i16,
+        second_effect: i16,
+    },
+    Anvil {
+        repair_cost: i16,
+    },
+    BrewingStand {
+        brew_time: i16,
+        fuel: i16,
+    },
+    Stonecutter {
+        selected_recipe: i16,
+    },
+    Loom {
+        selected_pattern: i16,
+    },
+    Lectern {
+        page: i16,
+    },
+    None,
+}
+
+/// Highest property index used by a screen, plus one
+const PROPERTY_COUNT: usize = 10;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct Screen {
+    pub window_id: i32,
+    pub menu: MenuType,
+    pub title: TextComponent,
+    properties: [i16; PROPERTY_COUNT],
+}
+
+#[allow(dead_code)]
+impl Screen {
+    pub fn properties(&self) -> ScreenProperties {
+        let p = self.properties;
+        match self.menu {
+            MenuType::Furnace | MenuType::BlastFurnace | MenuType::Smoker => {
+                ScreenProperties::Furnace {
+                    fuel_left: p[0],
+                    max_fuel: p[1],
+                    progress: p[2],
+                    max_progress: p[3],
+                }
+            }
+            MenuType::Enchantment => ScreenProperties::Enchantment {
+                level_requirements: [p[0], p[1], p[2]],
+                seed: p[3],
+                enchantments: [p[4], p[5], p[6]],
+                levels: [p[7], p[8], p[9]],
+            },
+            MenuType::Beacon => ScreenProperties::Beacon {
+                power_level: p[0],
+                first_effect: p[1],
+                second_effect: p[2],
+            },
+            MenuType::Anvil => ScreenProperties::Anvil { repair_cost: p[0] },
+            MenuType::BrewingStand => ScreenProperties::BrewingStand {
+                brew_time: p[0],
+                fuel: p[1],
+            },
+            MenuType::Stonecutter => ScreenProperties::Stonecutter {
+                selected_recipe: p[0],
+            },
+            MenuType::Loom => ScreenProperties::Loom {
+                selected_pattern: p[0],
+            },
+            MenuType::Lectern => ScreenProperties::Lectern { page: p[0] },
+            _ => ScreenProperties::None,
+        }
+    }
+
+    /// Smelting progress of a furnace, between 0 and 1
+    pub fn furnace_progress(&self) -> Option<f32> {
+        match self.properties() {
+            ScreenProperties::Furnace {
+                progress,
+                max_progress,
+                ..
+            } if max_progress > 0 => Some(progress as f32 / max_progress as f32),
+            _ => None,
+        }
+    }
+}
+
+/// The screen opened by the server, if any
+#[derive(Debug, Default)]
+pub struct Windows {
+    pub screen: Option<Screen>,
+    /// Waiting for a screen to open after an interaction with a block
+    opening: Option<Sender<Screen>>,
+}
+
+impl Windows {
+    pub fn screen_opened(&mut self, window_id: i32, menu_id: i32, title: TextComponent) {
+        let Some(menu) = MenuType::from_id(menu_id) else {
+            warn!("Unknown menu type {}", menu_id);
+            return;
+        };
+        let screen = Screen {
+            window_id,
+            menu,
+            title,
+            properties: [0; PROPERTY_COUNT],
+        };
+        if let Some(sender) = self.opening.take() {
+            // The requester may have given up waiting
+            let _ = sender.send(screen.clone());
+        }
+        self.screen = Some(screen);
+    }
+
+    pub fn set_property(&mut self, window_id: i32, property: i16, value: i16) {
+        let Some(screen) = self.screen.as_mut().filter(|s| s.window_id == window_id) else {
+            warn!("Property of unknown window {}", window_id);
+            return;
+        };
+        match screen.properties.get_mut(property as usize) {
+            Some(p) => *p = value,
+            None => warn!("Unknown property {} of {:?}", property, screen.menu),
+        }
+    }
+}
+
+impl Game {
+    /// Screen opened by the server, with its slots tracked in `inventory.container`
+    pub fn open_screen(&mut self, window_id: i32, menu_id: i32, title: TextComponent) {
+        self.inventory.container = Some(Window::new(window_id, 0));
+        self.windows.screen_opened(window_id, menu_id, title);
+    }
+
+    /// Screen closed by the server
+    pub fn screen_closed(&mut self, window_id: i32) {
+        if self.windows.screen.as_ref().map(|s| s.window_id) == Some(window_id) {
+            self.windows.screen = None;
+        }
+        if self.inventory.container.as_ref().map(|w| w.id) == Some(window_id) {
+            self.inventory.container = None;
+        }
+    }
+
+    /// Use the block at `pos` (chest, furnace, crafting table...), the receiver gets the screen
+    /// once the server opens it
+    #[allow(dead_code)]
+    pub fn open_container(&mut self, pos: BlockPos) -> Result<Receiver<Screen>, ReceiveError> {
+        let (sender, receiver) = channel();
+        self.windows.opening = Some(sender);
+
+        self.use_item_on(pos, BlockFace::Top, BlockFace::Top.center(), Hand::Main)?;
+
+        Ok(receiver)
+    }
+
+    /// Close the open screen, if any
+    #[allow(dead_code)]
+    pub fn close_container(&mut self) -> Result<(), ReceiveError> {
+        let window_id = self
+            .windows
+            .screen
+            .as_ref()
+            .map(|s| s.window_id)
+            .or(self.inventory.container.as_ref().map(|w| w.id))
+            .unwrap_or(PLAYER_WINDOW_ID);
+
+        send_packet_from_thread(CloseContainer {
+            window_id: VarInt(window_id),
+        })?;
+        self.screen_closed(window_id);
+        Ok(())
+    }
+}
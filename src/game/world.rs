@@ -1,11 +1,15 @@
-use std::collections::{HashMap, hash_map::Entry};
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    sync::Arc,
+};
 
+use log::warn;
 use parking_lot::RwLock;
 
 use crate::{
     datatypes::BlockPos,
     game::{
-        ChunkPos, ChunkSectionPos, LocalPos,
+        Aabb, BlockState, ChunkPos, ChunkSectionPos, DimensionType, LocalPos,
         world::{
             data::{ChunkData, ProtocolChunkSection},
             palette::{Palette, palette_config},
@@ -16,39 +20,115 @@ use crate::{
 pub mod data;
 mod palette;
 
+/// Vertical extent of the blocks of a dimension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorldHeight {
+    pub min_y: i32,
+    pub height: i32,
+}
+
+impl WorldHeight {
+    pub const OVERWORLD: Self = Self {
+        min_y: -64,
+        height: 384,
+    };
+
+    pub fn min_section(self) -> i32 {
+        self.min_y >> 4
+    }
+
+    pub fn section_count(self) -> usize {
+        (self.height / 16) as usize
+    }
+}
+
+impl Default for WorldHeight {
+    fn default() -> Self {
+        Self::OVERWORLD
+    }
+}
+
+impl From<&DimensionType> for WorldHeight {
+    fn from(dimension: &DimensionType) -> Self {
+        Self {
+            min_y: dimension.min_y,
+            height: dimension.height,
+        }
+    }
+}
+
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct Chunk {
     heightmaps: Vec<data::Heightmap>,
-    sections: [ChunkSection; 24],
+    /// Section Y of the lowest section
+    min_section: i32,
+    sections: Vec<ChunkSection>,
     block_entities: Vec<data::BlockEntity>,
 }
 
 impl Chunk {
-    fn empty() -> Self {
+    fn empty(height: WorldHeight) -> Self {
         Self {
             heightmaps: Vec::new(),
-            sections: [const { ChunkSection::empty() }; 24],
+            min_section: height.min_section(),
+            sections: (0..height.section_count())
+                .map(|_| ChunkSection::empty())
+                .collect(),
             block_entities: Vec::new(),
         }
     }
-}
 
-impl From<ChunkData> for Chunk {
-    fn from(
+    pub fn from_data(
         ChunkData {
             heightmaps,
             chunk_sections,
             block_entities,
-            ..
         }: ChunkData,
+        height: WorldHeight,
     ) -> Self {
+        if chunk_sections.len() != height.section_count() {
+            warn!(
+                "Chunk with {} sections in a dimension with {}",
+                chunk_sections.len(),
+                height.section_count()
+            );
+        }
         Self {
             heightmaps,
-            sections: chunk_sections.map(|v| v.into()),
+            min_section: height.min_section(),
+            sections: chunk_sections.into_iter().map(Into::into).collect(),
             block_entities,
         }
     }
+
+    fn section(&self, section_y: i32) -> Option<&ChunkSection> {
+        let index = usize::try_from(section_y - self.min_section).ok()?;
+        self.sections.get(index)
+    }
+
+    fn section_mut(&mut self, section_y: i32) -> Option<&mut ChunkSection> {
+        let index = usize::try_from(section_y - self.min_section).ok()?;
+        self.sections.get_mut(index)
+    }
+
+    /// Approximate memory used by the chunk, in bytes
+    fn memory_usage(&self) -> usize {
+        let heightmaps: usize = self
+            .heightmaps
+            .iter()
+            .map(|h| size_of_val(h) + h.data_size())
+            .sum();
+        let sections: usize = self
+            .sections
+            .iter()
+            .map(|s| size_of_val(s) + s.blocks.heap_size() + s.biomes.heap_size())
+            .sum();
+        size_of::<Self>()
+            + heightmaps
+            + sections
+            + self.block_entities.capacity() * size_of::<data::BlockEntity>()
+    }
 }
 
 #[derive(Debug)]
@@ -78,39 +158,90 @@ impl ChunkSection {
         }
     }
 
-    fn set_block(&mut self, pos: LocalPos, block: i32) {
-        let old = self.blocks.set(pos, block);
+    fn set_block(&mut self, pos: LocalPos, block: BlockState) {
+        let old = BlockState(self.blocks.set(pos, block.0));
         if old == block {
             return;
         }
-        if old == 0 {
+        if old.is_air() {
             self.block_count += 1;
         }
-        if block == 0 {
+        if block.is_air() {
             self.block_count -= 1;
         }
     }
 }
 
+/// Chunks the server keeps loaded around the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewArea {
+    pub center: ChunkPos,
+    /// View distance, in chunks
+    pub radius: i32,
+}
+
+impl Default for ViewArea {
+    fn default() -> Self {
+        Self {
+            center: ChunkPos::default(),
+            radius: 8,
+        }
+    }
+}
+
+impl ViewArea {
+    /// Chunks at most `margin` chunks out of the view distance are in range
+    pub fn in_range(&self, pos: ChunkPos, margin: i32) -> bool {
+        let distance = (pos.x - self.center.x)
+            .abs()
+            .max((pos.z - self.center.z).abs());
+        distance <= self.radius + margin
+    }
+}
+
+/// When the client unloads chunks on its own
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChunkEviction {
+    /// Only when the server forgets them
+    #[default]
+    Server,
+    /// Also when they are more than `margin` chunks out of the view area, in case the server
+    /// doesn't tell about some of them
+    OutOfView { margin: i32 },
+}
+
 #[derive(Debug, Default)]
+struct WorldInfo {
+    height: WorldHeight,
+    view: ViewArea,
+    eviction: ChunkEviction,
+}
+
+/// The loaded chunks, clones share the same chunks so they can be read from other threads
+#[derive(Debug, Default, Clone)]
 pub struct World {
-    chunks: RwLock<HashMap<ChunkPos, Chunk>>,
+    chunks: Arc<RwLock<HashMap<ChunkPos, Chunk>>>,
+    info: Arc<RwLock<WorldInfo>>,
 }
 
 impl World {
-    pub fn block_at(&self, pos: BlockPos) -> Option<i32> {
+    /// Blocks outside of the height of the dimension are air, `None` if the chunk isn't loaded
+    pub fn block_at(&self, pos: BlockPos) -> Option<BlockState> {
         let section_pos = ChunkSectionPos::from_block_pos(pos);
         let chunk_pos = ChunkPos::from(section_pos);
 
         let chunks = self.chunks.read();
         let chunk = chunks.get(&chunk_pos)?;
 
-        let section = &chunk.sections[(section_pos.y + 4) as usize];
+        let Some(section) = chunk.section(section_pos.y) else {
+            return Some(BlockState::AIR);
+        };
         let local_pos = LocalPos::from_global_block_pos(pos);
-        Some(section.blocks.get(local_pos))
+        Some(BlockState(section.blocks.get(local_pos)))
     }
 
-    pub fn set_block(&self, pos: BlockPos, block: i32) {
+    pub fn set_block(&self, pos: BlockPos, block: BlockState) {
         let section_pos = ChunkSectionPos::from_block_pos(pos);
         let local_pos = LocalPos::from_global_block_pos(pos);
 
@@ -118,19 +249,112 @@ impl World {
         let chunk = match chunks.entry(section_pos.into()) {
             Entry::Occupied(e) => e.into_mut(),
             Entry::Vacant(e) => {
-                if block == 0 {
-                    // air
+                if block.is_air() {
                     return;
                 }
 
-                e.insert(Chunk::empty())
+                e.insert(Chunk::empty(self.height()))
             }
         };
 
-        chunk.sections[(section_pos.y + 4) as usize].set_block(local_pos, block);
+        if let Some(section) = chunk.section_mut(section_pos.y) {
+            section.set_block(local_pos, block);
+        }
+    }
+
+    /// Change several blocks of a section with a single lock, ignored if its chunk isn't loaded
+    pub fn set_section_blocks(
+        &self,
+        section_pos: ChunkSectionPos,
+        blocks: &[(LocalPos, BlockState)],
+    ) {
+        let mut chunks = self.chunks.write();
+        let Some(section) = chunks
+            .get_mut(&section_pos.into())
+            .and_then(|chunk| chunk.section_mut(section_pos.y))
+        else {
+            return;
+        };
+        for (pos, block) in blocks {
+            section.set_block(*pos, *block);
+        }
+    }
+
+    /// Collision boxes of the block at `pos`, relative to the block, none if it isn't loaded
+    pub fn collision_boxes(&self, pos: BlockPos) -> &'static [Aabb] {
+        self.block_at(pos).map_or(&[], BlockState::collision_boxes)
+    }
+
+    pub fn is_chunk_loaded(&self, pos: ChunkPos) -> bool {
+        self.chunks.read().contains_key(&pos)
+    }
+
+    pub fn register_chunk_data(&self, pos: ChunkPos, data: ChunkData) {
+        let chunk = Chunk::from_data(data, self.height());
+        self.chunks.write().insert(pos, chunk);
+    }
+
+    /// Called when the server forgets the chunk
+    pub fn unload_chunk(&self, pos: ChunkPos) {
+        self.chunks.write().remove(&pos);
+    }
+
+    pub fn height(&self) -> WorldHeight {
+        self.info.read().height
+    }
+
+    /// Unload every chunk and use the height of another dimension
+    pub fn change_dimension(&self, height: WorldHeight) {
+        let mut chunks = self.chunks.write();
+        chunks.clear();
+        self.info.write().height = height;
     }
 
-    pub fn register_chunk_data(&self, pos: ChunkPos, data: Chunk) {
-        self.chunks.write().insert(pos, data);
+    #[allow(dead_code)]
+    pub fn view(&self) -> ViewArea {
+        self.info.read().view
+    }
+
+    pub fn set_view_center(&self, center: ChunkPos) {
+        self.info.write().view.center = center;
+        self.evict_chunks();
+    }
+
+    pub fn set_view_radius(&self, radius: i32) {
+        self.info.write().view.radius = radius;
+        self.evict_chunks();
+    }
+
+    #[allow(dead_code)]
+    pub fn set_eviction(&self, eviction: ChunkEviction) {
+        self.info.write().eviction = eviction;
+        self.evict_chunks();
+    }
+
+    /// Unload the chunks out of the view area, if the eviction policy says so
+    fn evict_chunks(&self) {
+        let (view, eviction) = {
+            let info = self.info.read();
+            (info.view, info.eviction)
+        };
+        let ChunkEviction::OutOfView { margin } = eviction else {
+            return;
+        };
+        self.chunks
+            .write()
+            .retain(|pos, _| view.in_range(*pos, margin));
+    }
+
+    #[allow(dead_code)]
+    pub fn loaded_chunks(&self) -> usize {
+        self.chunks.read().len()
+    }
+
+    /// Approximate memory used by the loaded chunks, in bytes
+    #[allow(dead_code)]
+    pub fn memory_usage(&self) -> usize {
+        let chunks = self.chunks.read();
+        let map = chunks.capacity() * (size_of::<(ChunkPos, Chunk)>() + 1);
+        map + chunks.values().map(Chunk::memory_usage).sum::<usize>()
     }
 }
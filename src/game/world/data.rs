@@ -7,19 +7,47 @@ use super::palette::palette_config::{self, PaletteConfig};
 use macros::Deserialize;
 
 use crate::{
-    data::Deserialize,
+    data::{DataStream, Deserialize, DeserializeError},
     datatypes::{BitSet, VarInt, deserialize_slice},
     nbt::Nbt,
 };
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug)]
 pub struct ChunkData {
     pub heightmaps: Vec<Heightmap>,
-    data_size: VarInt,
-    pub chunk_sections: [ProtocolChunkSection; 24], // 24 only in overworld
+    /// One per section of the dimension, from the bottom
+    pub chunk_sections: Vec<ProtocolChunkSection>,
     pub block_entities: Vec<BlockEntity>,
 }
 
+impl Deserialize for ChunkData {
+    fn deserialize(stream: &mut DataStream) -> Result<Self, DeserializeError> {
+        let heightmaps = Vec::deserialize(stream)?;
+
+        // The number of sections depends on the dimension, they are read until the end of the
+        // data instead
+        let data_size = usize::try_from(VarInt::deserialize(stream)?.0).map_err(|_| {
+            DeserializeError::MalformedPacket("Negative chunk data size".to_string())
+        })?;
+        if data_size > stream.remaining_size() {
+            return Err(DeserializeError::MalformedPacket(
+                "Chunk data larger than the packet".to_string(),
+            ));
+        }
+        let mut data = DataStream::new(stream, data_size);
+        let mut chunk_sections = Vec::new();
+        while data.remaining_size() > 0 {
+            chunk_sections.push(ProtocolChunkSection::deserialize(&mut data)?);
+        }
+
+        Ok(Self {
+            heightmaps,
+            chunk_sections,
+            block_entities: Vec::deserialize(stream)?,
+        })
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BlockEntity {
     xz: u8,
@@ -34,6 +62,13 @@ pub struct Heightmap {
     data: Vec<u64>,
 }
 
+impl Heightmap {
+    /// Size of the data allocated by the heightmap, in bytes
+    pub fn data_size(&self) -> usize {
+        self.data.capacity() * size_of::<u64>()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[enum_repr(VarInt)]
 pub enum HeightmapType {
@@ -124,7 +124,7 @@ impl<CONFIG: PaletteConfig> Palette<CONFIG> {
 
         data[long_idx] &= !mask;
         data[long_idx] |= value;
-        
+
         old_value as i32
     }
 
@@ -135,6 +135,24 @@ impl<CONFIG: PaletteConfig> Palette<CONFIG> {
         }
     }
 
+    /// Approximate size of the data allocated by the palette, in bytes
+    pub fn heap_size(&self) -> usize {
+        match self {
+            Palette::SingleValued { .. } => 0,
+            Palette::Indirect {
+                palette2id,
+                id2palette,
+                data,
+                ..
+            } => {
+                palette2id.capacity() * size_of::<i32>()
+                    + id2palette.capacity() * (size_of::<(i32, usize)>() + 1)
+                    + data.capacity() * size_of::<u64>()
+            }
+            Palette::Direct { data, .. } => data.capacity() * size_of::<u64>(),
+        }
+    }
+
     pub fn set(&mut self, pos: LocalPos, id: i32) -> i32 {
         match self {
             Palette::Direct { bpe, data } => Self::set_from_data(&mut *data, *bpe, pos, id),
@@ -27,7 +27,7 @@ pub enum NbtError {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Nbt {
     End,
     Byte(i8),
@@ -140,6 +140,64 @@ impl Nbt {
     }
 }
 
+impl Nbt {
+    /// Deserialize a network NBT whose root can be any tag (used by text components)
+    pub fn deserialize_any(stream: &mut DataStream) -> Result<Self, DeserializeError> {
+        let id = u8::deserialize(stream)?;
+        Self::deserialize_by_id(stream, id)
+    }
+
+    /// Get a field of a compound
+    pub fn get(&self, name: &str) -> Option<&Nbt> {
+        match self {
+            Self::Compound(map) => map.get(name),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get an integer value, whatever its size
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            Self::Byte(v) => Some(v as i64),
+            Self::Short(v) => Some(v as i64),
+            Self::Int(v) => Some(v as i64),
+            Self::Long(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Get a numeric value as a float
+    pub fn as_float(&self) -> Option<f64> {
+        match *self {
+            Self::Float(v) => Some(v as f64),
+            Self::Double(v) => Some(v),
+            _ => self.as_int().map(|v| v as f64),
+        }
+    }
+
+    /// Booleans are stored as bytes
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Self::Byte(v) => Some(v != 0),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Nbt]> {
+        match self {
+            Self::List(list) => Some(list),
+            _ => None,
+        }
+    }
+}
+
 impl Deserialize for Nbt {
     fn deserialize(stream: &mut DataStream) -> Result<Self, DeserializeError> {
         let id = u8::deserialize(stream)?;
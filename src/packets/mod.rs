@@ -2,8 +2,8 @@ mod receive;
 mod send;
 
 use core::f32;
-use std::ops::Deref;
 
+use log::warn;
 use parking_lot::RwLock;
 pub use receive::*;
 pub use send::*;
@@ -12,12 +12,19 @@ use macros::{Deserialize, Serialize};
 
 use crate::{
     bitflags,
-    data::{DataStream, Deserialize, DeserializeError, ReadWrite, Serialize, SerializeError},
-    datatypes::{Angle, BlockPos, LengthInferredByteArray, Or, VarInt},
+    data::{DataStream, Deserialize, DeserializeError, ReadWrite, Serialize},
+    datatypes::{
+        Angle, BitSet, BlockPos, LengthInferredByteArray, Or, TextComponent, VarInt, VarLong,
+    },
     game::{
-        ChunkPos, Color, Entity, EntityId, EntityRef, Game, GameError, IdSet, Rotation,
-        SlotDisplay, Vec3, Vec3d, entities,
-        world::data::{ChunkData, LightData},
+        Attribute, BlockFace, BlockState, ChatEntry, ChatKind, ChunkPos, ChunkSectionPos, Color,
+        CommandNode, Entity, EntityId, EntityMetadata, EntityRef, EntityType, EquipmentSlot, Game,
+        GameError, GameEvent, GameMode, Hand, IdSet, LocalPos, MobEffect, Recipes, Rotation, Slot,
+        SlotDisplay, Suggestions, Vec3, Vec3d, entities,
+        world::{
+            WorldHeight,
+            data::{ChunkData, LightData},
+        },
     },
     nbt::Nbt,
 };
@@ -67,7 +74,7 @@ pub struct LoginSuccess {
     pub property: Vec<PlayerProperty>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 #[allow(dead_code)]
 pub struct PlayerProperty {
     pub name: String,
@@ -134,11 +141,13 @@ impl ClientboundPacket for KnownPacks {
     const STATE: ConnectionState = ConnectionState::Configuration;
 
     fn receive(self, stream: &mut dyn ReadWrite, _game: &RwLock<Game>) -> Result<(), ReceiveError> {
-        send_packet(stream, self)?;
+        // Don't claim to know any pack, so the server sends the data of every registry entry
+        send_packet(stream, KnownPacks(Vec::new()))?;
         Ok(())
     }
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct KnownPack {
     pub namespace: String,
@@ -171,6 +180,12 @@ pub struct RegistryData {
 impl ClientboundPacket for RegistryData {
     const ID: u32 = 7;
     const STATE: ConnectionState = ConnectionState::Configuration;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let entries = self.entries.into_iter().map(Into::into).collect();
+        game.write().registries.insert(self.registry_id, entries);
+        Ok(())
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -183,17 +198,41 @@ pub struct RegistryDataEntry {
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
 pub struct UpdateTags {
-    pub tags_array: Vec<(String, Tags)>,
+    pub tags_array: Vec<(String, RegistryTags)>,
 }
 
 impl ClientboundPacket for UpdateTags {
     const ID: u32 = 0x0D;
     const STATE: ConnectionState = ConnectionState::Configuration;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let mut game = game.write();
+        for (registry, RegistryTags(tags)) in self.tags_array {
+            let tags = tags
+                .into_iter()
+                .map(|(name, ids)| (name, ids.into_iter().map(|id| id.0).collect()))
+                .collect();
+            game.tags.set(registry, tags);
+        }
+        Ok(())
+    }
+}
+
+/// Same as [`UpdateTags`], when received in play state
+#[derive(Debug, Deserialize)]
+pub struct PlayUpdateTags(UpdateTags);
+
+impl ClientboundPacket for PlayUpdateTags {
+    const ID: u32 = 0x7F;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        self.0.receive(stream, game)
+    }
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
-pub struct Tags(Vec<(String, Vec<VarInt>)>);
+pub struct RegistryTags(Vec<(String, Vec<VarInt>)>);
 
 #[allow(dead_code)]
 #[derive(Debug, Deserialize)]
@@ -207,8 +246,16 @@ pub struct Login {
     pub reduced_debug_info: bool,
     pub enable_respawn_screen: bool,
     pub limited_crafting: bool,
+    pub spawn_info: SpawnInfo,
+    pub enforce_secure_chat: bool,
+}
+
+/// Dimension and game mode of the player, sent on login and respawn
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct SpawnInfo {
     pub dimension_type: VarInt,
-    pub dimesion_name: String,
+    pub dimension_name: String,
     pub hashed_seed: i64,
     pub game_mode: u8,
     pub previous_game_mode: i8,
@@ -217,7 +264,6 @@ pub struct Login {
     pub death_location: Option<DeathLocation>,
     pub portal_cooldown: VarInt,
     pub sea_level: VarInt,
-    pub enforce_secure_chat: bool,
 }
 
 #[allow(dead_code)]
@@ -237,7 +283,75 @@ impl ClientboundPacket for Login {
         let entity = game.player.entity.read().clone();
         let entity_ref = game.entities.add(self.entity_id, entity);
         game.player.entity = entity_ref;
+        game.player.entity_id = self.entity_id;
+        spawn(&mut game, &self.spawn_info);
+        game.world.set_view_radius(self.view_distance.0);
+
+        game.chat.set_secure_chat_enforced(self.enforce_secure_chat);
+
+        Ok(())
+    }
+}
+
+/// Enter the dimension of `info`, the world is emptied if it's another one
+fn spawn(game: &mut Game, info: &SpawnInfo) {
+    game.player.game_mode = GameMode::from_id(info.game_mode as i32).unwrap_or_default();
+    if game.player.dimension == info.dimension_name {
+        return;
+    }
+
+    let height = match game.registries.dimension_type(info.dimension_type.0) {
+        Some(dimension) => WorldHeight::from(&dimension),
+        None => {
+            warn!("Unknown dimension type {}", info.dimension_type.0);
+            WorldHeight::default()
+        }
+    };
+    game.player.dimension = info.dimension_name.clone();
+    game.world.change_dimension(height);
+    game.entities.retain(|id, _| id == game.player.entity_id);
+    game.predictions = Default::default();
+    game.digging = None;
+    game.stop_moving();
+}
+
+bitflags! {
+    #[derive(Debug)]
+    pub struct RespawnDataKept: u8 {
+        const ATTRIBUTES = 1;
+        const METADATA = 2;
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct Respawn {
+    pub spawn_info: SpawnInfo,
+    pub data_kept: RespawnDataKept,
+}
+
+impl ClientboundPacket for Respawn {
+    const ID: u32 = 0x4B;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let mut game = game.write();
+        spawn(&mut game, &self.spawn_info);
+
+        // Like the vanilla client, the player is a new entity
+        let mut entity = game.player.entity.write();
+        if !self.data_kept.contains(RespawnDataKept::ATTRIBUTES) {
+            entity.attributes.clear();
+        }
+        if !self.data_kept.contains(RespawnDataKept::METADATA) {
+            entity.metadata = Default::default();
+        }
+        entity.speed = Vec3d::default();
+        drop(entity);
+        game.player.effects.clear();
+        game.player.physics = Default::default();
 
+        game.behaviors.push_event(GameEvent::Respawned);
         Ok(())
     }
 }
@@ -275,6 +389,13 @@ bitflags! {
 impl ClientboundPacket for PlayerAbilities {
     const ID: u32 = 0x39;
     const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let physics = &mut game.write().player.physics;
+        physics.flying = self.flags.contains(PlayerAbilitiesFlags::FLYING);
+        physics.flying_speed = self.flying_speed;
+        Ok(())
+    }
 }
 
 #[allow(dead_code)]
@@ -286,6 +407,11 @@ pub struct SetHeldItem {
 impl ClientboundPacket for SetHeldItem {
     const ID: u32 = 0x62;
     const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.write().inventory.set_selected(self.slot.0 as usize);
+        Ok(())
+    }
 }
 
 #[allow(dead_code)]
@@ -298,6 +424,18 @@ pub struct UpdateRecipes {
 impl ClientboundPacket for UpdateRecipes {
     const ID: u32 = 0x7E;
     const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.write().recipes = Recipes {
+            property_sets: self
+                .property_sets
+                .into_iter()
+                .map(|(name, ids)| (name, ids.into_iter().map(|id| id.0).collect()))
+                .collect(),
+            stonecutter: self.stonecutter_recipes,
+        };
+        Ok(())
+    }
 }
 
 #[allow(dead_code)]
@@ -442,48 +580,12 @@ fn update_entity_pos(
         y: dy as f64 / 4096.,
         z: dz as f64 / 4096.,
     };
-    entity.position += dpos;
+    let pos = entity.server_position + dpos;
+    entity.set_server_position(pos);
 
     Ok(entity)
 }
 
-// FIXME: Move it elsewhere
-fn entity_moved(
-    entity: &Entity,
-    stream: &mut dyn ReadWrite,
-    game: impl Deref<Target = Game>,
-) -> Result<(), SerializeError> {
-    if entity.entity_type == 149 {
-        let pos_diff = entity.position - game.player.entity.read().position;
-
-        let mut yaw = -f64::atan2(pos_diff.x, pos_diff.z).to_degrees() as f32;
-        if yaw < 0. {
-            yaw += 360.;
-        }
-        let dist = Vec3d {
-            x: pos_diff.x,
-            y: 0.,
-            z: pos_diff.z,
-        }
-        .length();
-        let pitch = -f64::atan(pos_diff.y / dist).to_degrees() as f32;
-
-        let new_rotation = Rotation { yaw, pitch };
-
-        game.player.entity.write().rotation = new_rotation;
-        drop(game);
-
-        send_packet(
-            stream,
-            SetPlayerRotation {
-                rotation: new_rotation,
-                flags: PlayerPosFlags::empty(),
-            },
-        )?;
-    }
-    Ok(())
-}
-
 #[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct UpdateEntityPosition {
@@ -498,13 +600,14 @@ impl ClientboundPacket for UpdateEntityPosition {
     const ID: u32 = 0x2E;
     const STATE: ConnectionState = ConnectionState::Play;
 
-    fn receive(self, stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
         let entity_id = self.entity_id.into();
 
         let game = game.read();
 
-        let entity = update_entity_pos(entity_id, self.dx, self.dy, self.dz, &game)?;
-        entity_moved(&entity, stream, game)?;
+        let mut entity = update_entity_pos(entity_id, self.dx, self.dy, self.dz, &game)?;
+        entity.on_ground = self.on_ground;
+        game.behaviors.push_event(GameEvent::EntityMoved(entity_id));
 
         Ok(())
     }
@@ -526,13 +629,14 @@ impl ClientboundPacket for UpdateEntityPositionRotation {
     const ID: u32 = 0x2F;
     const STATE: ConnectionState = ConnectionState::Play;
 
-    fn receive(self, stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
         let entity_id = self.entity_id.into();
 
         let game = game.read();
 
         let mut entity = update_entity_pos(entity_id, self.dx, self.dy, self.dz, &game)?;
-        entity_moved(&entity, stream, game)?;
+        entity.on_ground = self.on_ground;
+        game.behaviors.push_event(GameEvent::EntityMoved(entity_id));
 
         entity.rotation = Rotation::from_angles(self.yaw, self.pitch);
 
@@ -555,7 +659,6 @@ pub struct SetPlayerPosition {
     pub flags: PlayerPosFlags,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Serialize)]
 #[sb_id = 0x1E]
 pub struct SetPlayerPositionRotation {
@@ -571,6 +674,12 @@ pub struct SetPlayerRotation {
     pub flags: PlayerPosFlags,
 }
 
+#[derive(Debug, Serialize)]
+#[sb_id = 0x20]
+pub struct SetPlayerMovementFlags {
+    pub flags: PlayerPosFlags,
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct PlayersInfoUpdate {
@@ -580,6 +689,50 @@ pub struct PlayersInfoUpdate {
 impl ClientboundPacket for PlayersInfoUpdate {
     const ID: u32 = 0x3F;
     const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let mut game = game.write();
+        for (uuid, actions) in self.players {
+            game.player_list.update(uuid, actions);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PlayerInfoRemove {
+    pub players: Vec<u128>,
+}
+
+impl ClientboundPacket for PlayerInfoRemove {
+    const ID: u32 = 0x3E;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let mut game = game.write();
+        for uuid in self.players {
+            game.player_list.remove(uuid);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TabListHeaderFooter {
+    pub header: TextComponent,
+    pub footer: TextComponent,
+}
+
+impl ClientboundPacket for TabListHeaderFooter {
+    const ID: u32 = 0x73;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let mut game = game.write();
+        game.player_list.header = Some(self.header);
+        game.player_list.footer = Some(self.footer);
+        Ok(())
+    }
 }
 
 impl Deserialize for PlayersInfoUpdate {
@@ -657,7 +810,7 @@ pub enum PlayerAction {
     UpdateGameMode(VarInt),
     UpdateListed(bool),
     UpdateLatency(VarInt),
-    UpdateDisplayName(Option<String>),
+    UpdateDisplayName(Option<TextComponent>),
     UpdateListPriority(VarInt),
     UpdateHat(bool),
 }
@@ -676,7 +829,7 @@ pub struct InitializeChatData {
 pub struct AddEntity {
     pub entity_id: VarInt,
     pub uuid: u128,
-    pub entity_type: VarInt,
+    pub entity_type: EntityType,
     pub pos: Vec3d,
     pub pitch: Angle,
     pub yaw: Angle,
@@ -695,11 +848,17 @@ impl ClientboundPacket for AddEntity {
         let entity = Entity {
             uuid: self.uuid,
             position: self.pos,
+            server_position: self.pos,
             rotation: Rotation::from_angles(self.yaw, self.pitch),
             speed: Vec3d::speed_from_entity_velocity(self.vx, self.vy, self.vz),
-            entity_type: self.entity_type.into(),
+            head_yaw: self.head_yaw.into(),
+            entity_type: self.entity_type,
+            ..Default::default()
         };
-        game.read().entities.add(self.entity_id.into(), entity);
+        let game = game.read();
+        game.entities.add(self.entity_id.into(), entity);
+        game.behaviors
+            .push_event(GameEvent::EntityAdded(self.entity_id.into()));
 
         Ok(())
     }
@@ -733,7 +892,7 @@ impl ClientboundPacket for TeleportEntity {
     const ID: u32 = 0x1F;
     const STATE: ConnectionState = ConnectionState::Play;
 
-    fn receive(self, stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
         let id = self.entity_id.into();
 
         let game = game.read();
@@ -743,11 +902,12 @@ impl ClientboundPacket for TeleportEntity {
             .get_mut(id)
             .ok_or(GameError::UnkonwnEntity(id))?;
 
-        entity.position = self.pos;
+        entity.teleport(self.pos);
         entity.speed = self.speed;
         entity.rotation = self.rotation;
+        entity.on_ground = self.on_ground;
 
-        entity_moved(&entity, stream, game)?;
+        game.behaviors.push_event(GameEvent::EntityMoved(id));
 
         Ok(())
     }
@@ -779,6 +939,149 @@ impl ClientboundPacket for SetEntityVelocity {
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RemoveEntities {
+    pub entity_ids: Vec<VarInt>,
+}
+
+impl ClientboundPacket for RemoveEntities {
+    const ID: u32 = 0x46;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let game = game.read();
+        for id in self.entity_ids {
+            game.entities.remove(id.into());
+            game.behaviors
+                .push_event(GameEvent::EntityRemoved(id.into()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetHeadRotation {
+    pub entity_id: VarInt,
+    pub head_yaw: Angle,
+}
+
+impl ClientboundPacket for SetHeadRotation {
+    const ID: u32 = 0x4C;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let id = self.entity_id.into();
+        let mut entity = game
+            .read()
+            .entities
+            .get_mut(id)
+            .ok_or(GameError::UnkonwnEntity(id))?;
+
+        entity.head_yaw = self.head_yaw.into();
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetEntityMetadata {
+    pub entity_id: VarInt,
+    pub metadata: EntityMetadata,
+}
+
+impl ClientboundPacket for SetEntityMetadata {
+    const ID: u32 = 0x5C;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let id = self.entity_id.into();
+        let mut entity = game
+            .read()
+            .entities
+            .get_mut(id)
+            .ok_or(GameError::UnkonwnEntity(id))?;
+
+        entity.metadata.update(self.metadata);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct SetEquipment {
+    pub entity_id: VarInt,
+    pub equipment: Vec<(EquipmentSlot, Slot)>,
+}
+
+impl Deserialize for SetEquipment {
+    fn deserialize(stream: &mut DataStream) -> Result<Self, DeserializeError> {
+        // The top bit of the slot is set when another entry follows
+        const HAS_NEXT: u8 = 0x80;
+
+        let entity_id = VarInt::deserialize(stream)?;
+        let mut equipment = Vec::new();
+        loop {
+            let slot = u8::deserialize(stream)?;
+            let equipment_slot = EquipmentSlot::from_id(slot & !HAS_NEXT).ok_or_else(|| {
+                DeserializeError::MalformedPacket(format!("Unknown equipment slot {}", slot))
+            })?;
+            equipment.push((equipment_slot, Slot::deserialize(stream)?));
+            if slot & HAS_NEXT == 0 {
+                return Ok(Self {
+                    entity_id,
+                    equipment,
+                });
+            }
+        }
+    }
+}
+
+impl ClientboundPacket for SetEquipment {
+    const ID: u32 = 0x5F;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let id = self.entity_id.into();
+        let mut entity = game
+            .read()
+            .entities
+            .get_mut(id)
+            .ok_or(GameError::UnkonwnEntity(id))?;
+
+        for (slot, item) in self.equipment {
+            entity.equipment[slot as usize] = item;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAttributes {
+    pub entity_id: VarInt,
+    pub attributes: Vec<(VarInt, Attribute)>,
+}
+
+impl ClientboundPacket for UpdateAttributes {
+    const ID: u32 = 0x7C;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let id = self.entity_id.into();
+        let mut entity = game
+            .read()
+            .entities
+            .get_mut(id)
+            .ok_or(GameError::UnkonwnEntity(id))?;
+
+        for (attribute_id, attribute) in self.attributes {
+            entity.attributes.insert(attribute_id.0, attribute);
+        }
+
+        Ok(())
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct ChunkDataWithLight {
@@ -798,12 +1101,65 @@ impl ClientboundPacket for ChunkDataWithLight {
                 x: self.x,
                 z: self.z,
             },
-            self.data.into(),
+            self.data,
         );
         Ok(())
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ForgetLevelChunk {
+    pub z: i32,
+    pub x: i32,
+}
+
+impl ClientboundPacket for ForgetLevelChunk {
+    const ID: u32 = 0x21;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.read().world.unload_chunk(ChunkPos {
+            x: self.x,
+            z: self.z,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetChunkCacheCenter {
+    pub x: VarInt,
+    pub z: VarInt,
+}
+
+impl ClientboundPacket for SetChunkCacheCenter {
+    const ID: u32 = 0x57;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.read().world.set_view_center(ChunkPos {
+            x: self.x.0,
+            z: self.z.0,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetChunkCacheRadius {
+    pub view_distance: VarInt,
+}
+
+impl ClientboundPacket for SetChunkCacheRadius {
+    const ID: u32 = 0x58;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.read().world.set_view_radius(self.view_distance.0);
+        Ok(())
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct ChunkBatchFinished {
@@ -842,8 +1198,638 @@ impl ClientboundPacket for BlockUpdate {
     const STATE: ConnectionState = ConnectionState::Play;
 
     fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
-        game.read().world.set_block(self.pos, self.id.0);
+        game.write().block_update(self.pos, BlockState(self.id.0));
 
         Ok(())
     }
 }
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSectionBlocks {
+    /// Packed section position, 22 bits for X, 22 for Z and 20 for Y
+    section: i64,
+    /// Block state ID shifted by 12 bits, then the position in the section on 4 bits each for X,
+    /// Z and Y
+    blocks: Vec<VarLong>,
+}
+
+impl UpdateSectionBlocks {
+    fn section_pos(&self) -> ChunkSectionPos {
+        ChunkSectionPos {
+            x: (self.section >> 42) as i32,
+            y: (self.section << 44 >> 44) as i32,
+            z: (self.section << 22 >> 42) as i32,
+        }
+    }
+}
+
+impl ClientboundPacket for UpdateSectionBlocks {
+    const ID: u32 = 0x4D;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|block| {
+                let pos = LocalPos {
+                    x: (block.0 >> 8 & 0xF) as u8,
+                    y: (block.0 & 0xF) as u8,
+                    z: (block.0 >> 4 & 0xF) as u8,
+                };
+                (pos, BlockState((block.0 >> 12) as i32))
+            })
+            .collect();
+        game.write()
+            .section_blocks_update(self.section_pos(), blocks);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlockChangedAck {
+    pub sequence: VarInt,
+}
+
+impl ClientboundPacket for BlockChangedAck {
+    const ID: u32 = 0x04;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.write().block_changed_ack(self.sequence.0);
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Serialize)]
+#[enum_repr(VarInt)]
+pub enum DigStatus {
+    Start,
+    Abort,
+    Finish,
+    DropItemStack,
+    DropItem,
+    ReleaseUseItem,
+    SwapItemWithOffhand,
+}
+
+/// Player Action, named after its main use
+#[derive(Debug, Serialize)]
+#[sb_id = 0x28]
+pub struct PlayerDigging {
+    pub status: DigStatus,
+    pub location: BlockPos,
+    /// Sent as a byte, which is encoded like a VarInt for these values
+    pub face: BlockFace,
+    pub sequence: VarInt,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct UpdateMobEffect {
+    pub entity_id: VarInt,
+    pub effect_id: VarInt,
+    pub amplifier: VarInt,
+    pub duration: VarInt,
+    pub flags: u8,
+}
+
+impl ClientboundPacket for UpdateMobEffect {
+    const ID: u32 = 0x7D;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let mut game = game.write();
+        if EntityId::from(self.entity_id) == game.player.entity_id {
+            game.player.effects.insert(
+                self.effect_id.0,
+                MobEffect {
+                    amplifier: self.amplifier.0,
+                    duration: self.duration.0,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RemoveMobEffect {
+    pub entity_id: VarInt,
+    pub effect_id: VarInt,
+}
+
+impl ClientboundPacket for RemoveMobEffect {
+    const ID: u32 = 0x47;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let mut game = game.write();
+        if EntityId::from(self.entity_id) == game.player.entity_id {
+            game.player.effects.remove(&self.effect_id.0);
+        }
+        Ok(())
+    }
+}
+
+// Chat
+
+pub type MessageSignature = [u8; 256];
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct SystemChat {
+    pub content: TextComponent,
+    pub overlay: bool,
+}
+
+impl ClientboundPacket for SystemChat {
+    const ID: u32 = 0x72;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.write().chat.push(ChatEntry {
+            kind: ChatKind::System {
+                overlay: self.overlay,
+            },
+            sender: None,
+            content: self.content.to_plain(),
+        });
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct PlayerChat {
+    pub global_index: VarInt,
+    pub sender: u128,
+    pub index: VarInt,
+    pub signature: Option<MessageSignature>,
+    pub message: String,
+    pub timestamp: i64,
+    pub salt: i64,
+    pub previous_messages: Vec<PreviousMessage>,
+    pub unsigned_content: Option<TextComponent>,
+    pub filter: FilterType,
+    pub chat_type: ChatTypeRef,
+    pub sender_name: TextComponent,
+    pub target_name: Option<TextComponent>,
+}
+
+impl ClientboundPacket for PlayerChat {
+    const ID: u32 = 0x3A;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let mut game = game.write();
+
+        let content = match self.unsigned_content {
+            Some(content) => content.to_plain(),
+            None => self.message,
+        };
+        game.chat.push(ChatEntry {
+            kind: ChatKind::Player {
+                sender_uuid: self.sender,
+                chat_type: self.chat_type.registry_id(),
+            },
+            sender: Some(self.sender_name.to_plain()),
+            content,
+        });
+
+        let to_acknowledge = game.chat.message_received(self.signature);
+        drop(game);
+
+        if let Some(offset) = to_acknowledge {
+            send_packet(
+                stream,
+                MessageAcknowledgment {
+                    offset: VarInt(offset),
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum PreviousMessage {
+    Id(i32),
+    Signature(Box<MessageSignature>),
+}
+
+impl Deserialize for PreviousMessage {
+    fn deserialize(stream: &mut DataStream) -> Result<Self, DeserializeError> {
+        let id = VarInt::deserialize(stream)?.0;
+        if id == 0 {
+            Ok(Self::Signature(Box::deserialize(stream)?))
+        } else {
+            Ok(Self::Id(id - 1))
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+#[enum_repr(VarInt)]
+pub enum FilterType {
+    PassThrough,
+    FullyFiltered,
+    PartiallyFiltered(BitSet),
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum ChatTypeRef {
+    Registry(i32),
+    Inline(Box<ChatTypeData>),
+}
+
+impl ChatTypeRef {
+    pub fn registry_id(&self) -> Option<i32> {
+        match self {
+            Self::Registry(id) => Some(*id),
+            Self::Inline(_) => None,
+        }
+    }
+}
+
+impl Deserialize for ChatTypeRef {
+    fn deserialize(stream: &mut DataStream) -> Result<Self, DeserializeError> {
+        let id = VarInt::deserialize(stream)?.0;
+        if id == 0 {
+            Ok(Self::Inline(Box::deserialize(stream)?))
+        } else {
+            Ok(Self::Registry(id - 1))
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct ChatTypeData {
+    pub chat: ChatDecoration,
+    pub narration: ChatDecoration,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct ChatDecoration {
+    pub translation_key: String,
+    pub parameters: Vec<VarInt>,
+    pub style: Nbt,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct DisguisedChat {
+    pub message: TextComponent,
+    pub chat_type: ChatTypeRef,
+    pub sender_name: TextComponent,
+    pub target_name: Option<TextComponent>,
+}
+
+impl ClientboundPacket for DisguisedChat {
+    const ID: u32 = 0x1D;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.write().chat.push(ChatEntry {
+            kind: ChatKind::Disguised {
+                chat_type: self.chat_type.registry_id(),
+            },
+            sender: Some(self.sender_name.to_plain()),
+            content: self.message.to_plain(),
+        });
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[sb_id = 0x05]
+pub struct MessageAcknowledgment {
+    pub offset: VarInt,
+}
+
+#[derive(Debug, Serialize)]
+#[sb_id = 0x06]
+pub struct ChatCommand {
+    pub command: String,
+}
+
+#[derive(Debug, Serialize)]
+#[sb_id = 0x08]
+pub struct ChatMessage {
+    pub message: String,
+    pub timestamp: i64,
+    pub salt: i64,
+    pub signature: Option<MessageSignature>,
+    pub message_count: VarInt,
+    pub acknowledged: [u8; 3], // Fixed BitSet (20)
+    pub checksum: u8,
+}
+
+// Commands
+
+#[derive(Debug, Deserialize)]
+pub struct Commands {
+    pub nodes: Vec<CommandNode>,
+    pub root_index: VarInt,
+}
+
+impl ClientboundPacket for Commands {
+    const ID: u32 = 0x10;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let root = self.root_index.0 as usize;
+        if root >= self.nodes.len() {
+            return Err(DeserializeError::MalformedPacket(format!(
+                "Commands: root index {} out of bounds",
+                root
+            ))
+            .into());
+        }
+        // Walking the tree indexes the nodes directly
+        let len = self.nodes.len();
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Some(index) = node
+                .children
+                .iter()
+                .chain(&node.redirect)
+                .find(|&&c| c >= len)
+            {
+                return Err(DeserializeError::MalformedPacket(format!(
+                    "Commands: node {} references node {} out of bounds",
+                    i, index
+                ))
+                .into());
+            }
+        }
+
+        game.write().commands.set_tree(self.nodes, root);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[sb_id = 0x0E]
+pub struct CommandSuggestionsRequest {
+    pub transaction_id: VarInt,
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommandSuggestionsResponse {
+    pub transaction_id: VarInt,
+    pub start: VarInt,
+    pub length: VarInt,
+    pub matches: Vec<(String, Option<TextComponent>)>,
+}
+
+impl ClientboundPacket for CommandSuggestionsResponse {
+    const ID: u32 = 0x0F;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        // The request contains the leading '/' which isn't part of the command for the caller
+        let suggestions = Suggestions {
+            start: (self.start.0 as usize).saturating_sub(1),
+            length: self.length.0 as usize,
+            matches: self.matches,
+        };
+        game.write()
+            .commands
+            .suggestions_received(self.transaction_id.0, suggestions);
+        Ok(())
+    }
+}
+
+// Inventory
+
+#[derive(Debug, Deserialize)]
+pub struct SetContainerContent {
+    pub window_id: VarInt,
+    pub state_id: VarInt,
+    pub slots: Vec<Slot>,
+    pub carried_item: Slot,
+}
+
+impl ClientboundPacket for SetContainerContent {
+    const ID: u32 = 0x12;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.write().inventory.set_content(
+            self.window_id.0,
+            self.state_id.0,
+            self.slots,
+            self.carried_item,
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetContainerSlot {
+    pub window_id: VarInt,
+    pub state_id: VarInt,
+    pub slot: i16,
+    pub data: Slot,
+}
+
+impl ClientboundPacket for SetContainerSlot {
+    const ID: u32 = 0x14;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        let mut game = game.write();
+        if self.slot < 0 {
+            // Slot -1 of window -1 is the cursor
+            if self.window_id.0 == -1 {
+                game.inventory.cursor = self.data;
+            }
+            return Ok(());
+        }
+        game.inventory.set_slot(
+            self.window_id.0,
+            self.state_id.0,
+            self.slot as usize,
+            self.data,
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetCursorItem {
+    pub data: Slot,
+}
+
+impl ClientboundPacket for SetCursorItem {
+    const ID: u32 = 0x59;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.write().inventory.cursor = self.data;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetPlayerInventory {
+    pub slot: VarInt,
+    pub data: Slot,
+}
+
+impl ClientboundPacket for SetPlayerInventory {
+    const ID: u32 = 0x65;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        if self.slot.0 < 0 {
+            return Err(DeserializeError::MalformedPacket(format!(
+                "Negative player inventory slot ({})",
+                self.slot.0
+            ))
+            .into());
+        }
+        game.write()
+            .inventory
+            .set_player_slot(self.slot.0 as usize, self.data);
+        Ok(())
+    }
+}
+
+/// An item as sent by the client in clicks, the components being replaced by their hashes
+#[derive(Debug, Serialize)]
+pub struct HashedItem {
+    pub id: VarInt,
+    pub count: VarInt,
+    pub components_to_add: Vec<(VarInt, i32)>,
+    pub components_to_remove: Vec<VarInt>,
+}
+
+impl HashedItem {
+    /// The hashes of the components are not computed, so a stack with added components never
+    /// matches the one of the server, which sends the slot back
+    pub fn from_slot(slot: &Slot) -> Option<Self> {
+        match slot {
+            Slot::Empty => None,
+            Slot::NonEmpty {
+                count,
+                id,
+                components_to_remove,
+                ..
+            } => Some(Self {
+                id: *id,
+                count: *count,
+                components_to_add: Vec::new(),
+                components_to_remove: components_to_remove.clone(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[sb_id = 0x11]
+pub struct ClickContainer {
+    pub window_id: VarInt,
+    pub state_id: VarInt,
+    pub slot: i16,
+    pub button: i8,
+    pub mode: VarInt,
+    pub changed_slots: Vec<(i16, Option<HashedItem>)>,
+    pub carried_item: Option<HashedItem>,
+}
+
+/// Set Held Item (serverbound)
+#[derive(Debug, Serialize)]
+#[sb_id = 0x34]
+pub struct SetCarriedItem {
+    pub slot: i16,
+}
+
+// Windows
+
+#[derive(Debug, Deserialize)]
+pub struct OpenScreen {
+    pub window_id: VarInt,
+    pub window_type: VarInt,
+    pub title: TextComponent,
+}
+
+impl ClientboundPacket for OpenScreen {
+    const ID: u32 = 0x34;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.write()
+            .open_screen(self.window_id.0, self.window_type.0, self.title);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContainerClose {
+    pub window_id: VarInt,
+}
+
+impl ClientboundPacket for ContainerClose {
+    const ID: u32 = 0x11;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.write().screen_closed(self.window_id.0);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetContainerProperty {
+    pub window_id: VarInt,
+    pub property: i16,
+    pub value: i16,
+}
+
+impl ClientboundPacket for SetContainerProperty {
+    const ID: u32 = 0x13;
+    const STATE: ConnectionState = ConnectionState::Play;
+
+    fn receive(self, _stream: &mut dyn ReadWrite, game: &RwLock<Game>) -> Result<(), ReceiveError> {
+        game.write()
+            .windows
+            .set_property(self.window_id.0, self.property, self.value);
+        Ok(())
+    }
+}
+
+/// Close Container (serverbound)
+#[derive(Debug, Serialize)]
+#[sb_id = 0x12]
+pub struct CloseContainer {
+    pub window_id: VarInt,
+}
+
+#[derive(Debug, Serialize)]
+#[sb_id = 0x3F]
+pub struct UseItemOn {
+    pub hand: Hand,
+    pub location: BlockPos,
+    pub face: BlockFace,
+    pub cursor_x: f32,
+    pub cursor_y: f32,
+    pub cursor_z: f32,
+    pub inside_block: bool,
+    pub world_border_hit: bool,
+    pub sequence: VarInt,
+}
+
+#[derive(Debug, Serialize)]
+#[sb_id = 0x3C]
+pub struct SwingArm {
+    pub hand: Hand,
+}
@@ -9,7 +9,17 @@ use crate::{
     datatypes::{LengthInferredByteArray, VarInt},
     game::{Game, GameError},
     packets::{
-        AddEntity, BlockUpdate, ChangeDifficulty, ChunkBatchFinished, ChunkDataWithLight, EntityEvent, FeatureFlags, FinishConfiguration, KeepAlive, KnownPacks, Login, LoginSuccess, PlayerAbilities, PlayersInfoUpdate, PluginMessage, RegistryData, SetEntityVelocity, SetHeldItem, SynchronizePlayerPosition, TeleportEntity, UpdateEntityPosition, UpdateEntityPositionRotation, UpdateRecipes, UpdateTags, Waypoint
+        AddEntity, BlockChangedAck, BlockUpdate, ChangeDifficulty, ChunkBatchFinished,
+        ChunkDataWithLight, CommandSuggestionsResponse, Commands, ContainerClose, DisguisedChat,
+        EntityEvent, FeatureFlags, FinishConfiguration, ForgetLevelChunk, KeepAlive, KnownPacks,
+        Login, LoginSuccess, OpenScreen, PlayUpdateTags, PlayerAbilities, PlayerChat,
+        PlayerInfoRemove, PlayersInfoUpdate, PluginMessage, RegistryData, RemoveEntities,
+        RemoveMobEffect, Respawn, SetChunkCacheCenter, SetChunkCacheRadius, SetContainerContent,
+        SetContainerProperty, SetContainerSlot, SetCursorItem, SetEntityMetadata,
+        SetEntityVelocity, SetEquipment, SetHeadRotation, SetHeldItem, SetPlayerInventory,
+        SynchronizePlayerPosition, SystemChat, TabListHeaderFooter, TeleportEntity,
+        UpdateAttributes, UpdateEntityPosition, UpdateEntityPositionRotation, UpdateMobEffect,
+        UpdateRecipes, UpdateSectionBlocks, UpdateTags, Waypoint,
     },
 };
 
@@ -182,7 +192,35 @@ impl<'a> PacketReceiver<'a> {
             SetEntityVelocity,
             ChunkDataWithLight,
             ChunkBatchFinished,
-            BlockUpdate
+            BlockUpdate,
+            SystemChat,
+            PlayerChat,
+            DisguisedChat,
+            Commands,
+            CommandSuggestionsResponse,
+            PlayerInfoRemove,
+            TabListHeaderFooter,
+            PlayUpdateTags,
+            SetContainerContent,
+            SetContainerSlot,
+            SetCursorItem,
+            SetPlayerInventory,
+            OpenScreen,
+            ContainerClose,
+            SetContainerProperty,
+            BlockChangedAck,
+            UpdateMobEffect,
+            RemoveMobEffect,
+            RemoveEntities,
+            SetHeadRotation,
+            SetEntityMetadata,
+            SetEquipment,
+            UpdateAttributes,
+            Respawn,
+            ForgetLevelChunk,
+            SetChunkCacheCenter,
+            SetChunkCacheRadius,
+            UpdateSectionBlocks
         )
     }
 }